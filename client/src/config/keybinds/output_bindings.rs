@@ -5,12 +5,40 @@ use ui::input::KeyMatcher;
 #[derive(Debug, Clone, PartialStruct)]
 pub struct OutputBindings {
     pub toggle_wrap: Keybinding,
+    pub cycle_min_level: Keybinding,
+    pub search: Keybinding,
+    pub search_next: Keybinding,
+    pub search_prev: Keybinding,
+    pub filter: Keybinding,
+    pub toggle_source_picker: Keybinding,
+    pub export: Keybinding,
+    pub cycle_timestamps: Keybinding,
+    pub jump_to_time: Keybinding,
+    pub toggle_expand: Keybinding,
+    pub toggle_problems: Keybinding,
+    pub open_in_editor: Keybinding,
+    pub toggle_follow: Keybinding,
+    pub toggle_query_regex: Keybinding,
 }
 
 impl Default for OutputBindings {
     fn default() -> Self {
         Self {
             toggle_wrap: KeyMatcher::char('w').into(),
+            cycle_min_level: KeyMatcher::char('v').into(),
+            search: KeyMatcher::char('/').into(),
+            search_next: KeyMatcher::char('n').into(),
+            search_prev: KeyMatcher::char('n').shift().into(),
+            filter: KeyMatcher::char('f').into(),
+            toggle_source_picker: KeyMatcher::char('s').into(),
+            export: KeyMatcher::char('x').into(),
+            cycle_timestamps: KeyMatcher::char('t').into(),
+            jump_to_time: KeyMatcher::char('t').shift().into(),
+            toggle_expand: KeyMatcher::char('e').into(),
+            toggle_problems: KeyMatcher::char('p').into(),
+            open_in_editor: KeyMatcher::char('e').shift().into(),
+            toggle_follow: KeyMatcher::char('f').shift().into(),
+            toggle_query_regex: KeyMatcher::char('r').ctrl().into(),
         }
     }
 }
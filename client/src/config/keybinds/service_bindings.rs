@@ -8,6 +8,7 @@ pub struct ServiceBindings {
     pub toggle_output_all: Keybinding,
     pub toggle_automation_selected: Keybinding,
     pub toggle_automation_all: Keybinding,
+    pub toggle_block_details: Keybinding,
 }
 
 impl Default for ServiceBindings {
@@ -17,6 +18,7 @@ impl Default for ServiceBindings {
             toggle_output_all: KeyMatcher::char('o').shift().into(),
             toggle_automation_selected: KeyMatcher::char('a').into(),
             toggle_automation_all: KeyMatcher::char('a').shift().into(),
+            toggle_block_details: KeyMatcher::char('i').into(),
         }
     }
 }
@@ -1,11 +1,13 @@
 pub use keybinds::*;
 pub use models::*;
+pub use output::*;
 pub use parsing::read_config;
 pub use settings::*;
 pub use theme::*;
 
 mod keybinds;
 mod models;
+mod output;
 mod parsing;
 mod settings;
 mod theme;
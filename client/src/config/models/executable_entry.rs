@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 use serde_derive::{Deserialize, Serialize};
 
@@ -40,3 +41,39 @@ impl Display for ExecutableEntry {
         Ok(())
     }
 }
+
+/// A command run as a step of a sequence, i.e. one of the commands of a `cmd-seq` block or a command step of a task.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SequenceCommand {
+    #[serde(flatten)]
+    pub command: ExecutableEntry,
+    /// Time after which the command is killed and the attempt fails, no limit by default
+    #[serde(default, with = "humantime_serde")]
+    pub timeout: Option<Duration>,
+    /// Number of times the command is run again after a failed attempt
+    #[serde(default)]
+    pub retries: usize,
+    /// Time waited after a failed attempt before the command is run again
+    #[serde(default = "default_retry_delay", with = "humantime_serde")]
+    pub retry_delay: Duration,
+    /// Continues with the rest of the sequence even if all attempts fail
+    #[serde(default)]
+    pub allow_failure: bool,
+    /// Exit codes that count as success
+    #[serde(default = "default_exit_codes")]
+    pub exit_codes: Vec<i32>,
+}
+impl SequenceCommand {
+    pub fn attempts(&self) -> usize {
+        self.retries + 1
+    }
+}
+
+fn default_retry_delay() -> Duration {
+    Duration::from_secs(1)
+}
+
+fn default_exit_codes() -> Vec<i32> {
+    vec![0]
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use itertools::Itertools;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HttpRequirement {
+    pub url: String,
+    #[serde(default)]
+    pub method: HttpMethod,
+    #[serde(with = "humantime_serde")]
+    pub timeout: Duration,
+    /// Accepted status codes, defaults to any 2xx status
+    #[serde(default)]
+    pub status: HttpStatusMatcher,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Regex that the response body has to match
+    #[serde(default)]
+    pub body_pattern: Option<String>,
+    /// Values that the response body, parsed as JSON, has to contain at the given JSON pointers, e.g. `/status: UP`
+    #[serde(default)]
+    pub json: BTreeMap<String, serde_json::Value>,
+    /// Accepts invalid TLS certificates, such as the self-signed ones of development servers
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub enum HttpMethod {
+    #[default]
+    GET,
+    HEAD,
+    POST,
+    PATCH,
+    PUT,
+    DELETE,
+    OPTIONS,
+}
+impl Display for HttpMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            HttpMethod::GET => "GET",
+            HttpMethod::HEAD => "HEAD",
+            HttpMethod::POST => "POST",
+            HttpMethod::PATCH => "PATCH",
+            HttpMethod::PUT => "PUT",
+            HttpMethod::DELETE => "DELETE",
+            HttpMethod::OPTIONS => "OPTIONS",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Accepted status codes of an HTTP requirement, either a single entry or a list of them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum HttpStatusMatcher {
+    Single(HttpStatusEntry),
+    List(Vec<HttpStatusEntry>),
+}
+impl HttpStatusMatcher {
+    pub fn entries(&self) -> &[HttpStatusEntry] {
+        match self {
+            HttpStatusMatcher::Single(entry) => std::slice::from_ref(entry),
+            HttpStatusMatcher::List(entries) => entries,
+        }
+    }
+
+    pub fn matches(&self, status: u16) -> bool {
+        self.entries()
+            .iter()
+            .filter_map(|entry| entry.range())
+            .any(|(from, to)| (from..=to).contains(&status))
+    }
+}
+impl Default for HttpStatusMatcher {
+    fn default() -> Self {
+        HttpStatusMatcher::Single(HttpStatusEntry::Pattern("2xx".to_string()))
+    }
+}
+impl Display for HttpStatusMatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.entries().iter().join(", "))
+    }
+}
+
+/// A status code such as `200`, a class of codes such as `2xx`, or an inclusive range such as `200-299`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum HttpStatusEntry {
+    Code(u16),
+    Pattern(String),
+}
+impl HttpStatusEntry {
+    /// Inclusive range of the codes matched by the entry, or `None` if the pattern is malformed.
+    pub fn range(&self) -> Option<(u16, u16)> {
+        let pattern = match self {
+            HttpStatusEntry::Code(code) => return Some((*code, *code)),
+            HttpStatusEntry::Pattern(pattern) => pattern.trim(),
+        };
+
+        if let Some((from, to)) = pattern.split_once('-') {
+            let range = (from.trim().parse().ok()?, to.trim().parse().ok()?);
+            return (range.0 <= range.1).then_some(range);
+        }
+        if let Some(class) = pattern.to_lowercase().strip_suffix("xx") {
+            let class: u16 = class.parse().ok()?;
+            return (1..=5)
+                .contains(&class)
+                .then_some((class * 100, class * 100 + 99));
+        }
+        pattern.parse().ok().map(|code| (code, code))
+    }
+}
+impl Display for HttpStatusEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpStatusEntry::Code(code) => write!(f, "{code}"),
+            HttpStatusEntry::Pattern(pattern) => write!(f, "{pattern}"),
+        }
+    }
+}
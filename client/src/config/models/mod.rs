@@ -1,12 +1,14 @@
 pub use crate::config::settings::*;
 pub use automation_definition::*;
 pub use executable_entry::*;
+pub use http_requirement::*;
 pub use profile_definition::*;
 pub use requirement::*;
 pub use service_definition::*;
 
 mod automation_definition;
 mod executable_entry;
+mod http_requirement;
 mod profile_definition;
 mod requirement;
 mod service_definition;
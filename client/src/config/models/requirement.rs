@@ -1,50 +1,222 @@
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::time::Duration;
 
 use serde_derive::{Deserialize, Serialize};
 
+use crate::config::{ExecutableEntry, HttpMethod, HttpRequirement, HttpStatusMatcher, ServiceId};
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type", deny_unknown_fields)]
 pub enum Requirement {
     #[serde(rename = "http")]
-    Http {
-        url: String,
-        method: HttpMethod,
-        #[serde(with = "humantime_serde")]
-        timeout: Duration,
-        status: u16,
-    },
+    Http(HttpRequirement),
 
     #[serde(rename = "port")]
-    Port { port: u16, host: Option<String> },
+    Port {
+        port: u16,
+        host: Option<String>,
+        #[serde(default)]
+        mode: PortCheckMode,
+        /// Timeout of each connection attempt in `connect` mode
+        #[serde(default = "default_connect_timeout", with = "humantime_serde")]
+        timeout: Duration,
+    },
 
+    /// Succeeds if each of the globs matches a file or directory, optionally only counting them if they were modified
+    /// after the files matching `newer_than` or after the block started
     #[serde(rename = "file")]
-    File { paths: Vec<String> },
+    File {
+        paths: Vec<String>,
+        /// Globs of files, such as sources, that all matched files have to be newer than
+        #[serde(default)]
+        newer_than: Vec<String>,
+        /// Requires the matched files to have been modified since the latest run of the block started
+        #[serde(default)]
+        newer_than_block_start: bool,
+    },
+
+    /// Succeeds if a connection to a Unix domain socket can be established, relative to the workdir of the service
+    #[serde(rename = "unix_socket")]
+    UnixSocket {
+        path: String,
+        /// If set, an HTTP request is sent over the socket and its response is checked
+        #[serde(default)]
+        http: Option<UnixSocketHttp>,
+        #[serde(default = "default_connect_timeout", with = "humantime_serde")]
+        timeout: Duration,
+    },
 
     #[serde(rename = "script")]
     StateQuery { query: String },
+
+    /// Runs a command in the workdir of the service, succeeding if it exits with one of the given exit codes
+    #[serde(rename = "command")]
+    Command {
+        command: ExecutableEntry,
+        /// Time after which the command is killed and the check fails
+        #[serde(default = "default_command_timeout", with = "humantime_serde")]
+        timeout: Duration,
+        #[serde(default = "default_exit_codes")]
+        exit_codes: Vec<i32>,
+    },
+
+    /// Succeeds if an executable can be found, optionally with a matching version. Also checked when a profile is
+    /// selected, before any of its services start.
+    #[serde(rename = "executable")]
+    Executable(ExecutableRequirement),
+
+    /// Succeeds once the output of a block contains a line matching a regex. Only lines logged since the block was
+    /// last started count, or since the check started for output that isn't produced by a block.
+    #[serde(rename = "output")]
+    OutputPattern {
+        pattern: String,
+        /// If a line matching this regex is logged before one matching `pattern`, the check fails without retrying
+        #[serde(default)]
+        failure_pattern: Option<String>,
+        /// Service producing the output, defaults to the service of the block or task being checked
+        #[serde(default)]
+        service: Option<ServiceId>,
+        /// Block or task producing the output, defaults to the block being checked
+        #[serde(default)]
+        source: Option<String>,
+    },
+
+    /// Succeeds if any of the requirements succeeds, checking them in order
+    #[serde(rename = "any")]
+    Any {
+        requirements: Vec<Requirement>,
+        /// Time that checking each of the requirements may take before it counts as failed
+        #[serde(default = "default_branch_timeout", with = "humantime_serde")]
+        timeout: Duration,
+    },
+
+    /// Succeeds if all of the requirements succeed, checking them in order
+    #[serde(rename = "all")]
+    All {
+        requirements: Vec<Requirement>,
+        /// Time that checking each of the requirements may take before it counts as failed
+        #[serde(default = "default_branch_timeout", with = "humantime_serde")]
+        timeout: Duration,
+    },
+
+    /// Succeeds if the requirement fails, including when checking it times out
+    #[serde(rename = "not")]
+    Not {
+        requirement: Box<Requirement>,
+        #[serde(default = "default_branch_timeout", with = "humantime_serde")]
+        timeout: Duration,
+    },
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub enum HttpMethod {
-    GET,
-    POST,
-    PATCH,
-    PUT,
-    DELETE,
-    OPTIONS,
-}
-impl Display for HttpMethod {
+/// A short summary of what the requirement checks, such as `GET http://localhost:8080/health`.
+impl Display for Requirement {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let s = match self {
-            HttpMethod::GET => "GET",
-            HttpMethod::POST => "POST",
-            HttpMethod::PATCH => "PATCH",
-            HttpMethod::PUT => "PUT",
-            HttpMethod::DELETE => "DELETE",
-            HttpMethod::OPTIONS => "OPTIONS",
-        };
-        write!(f, "{}", s)
+        match self {
+            Requirement::Http(http) => write!(f, "{} {}", http.method, http.url),
+            Requirement::Port { port, host, .. } => {
+                write!(f, "port {}:{port}", host.as_deref().unwrap_or("localhost"))
+            }
+            Requirement::File { paths, .. } => write!(f, "file {}", paths.join(", ")),
+            Requirement::UnixSocket { path, http, .. } => match http {
+                Some(http) => write!(f, "{} {} on unix socket {path}", http.method, http.path),
+                None => write!(f, "unix socket {path}"),
+            },
+            Requirement::StateQuery { query } => write!(f, "script {query}"),
+            Requirement::Command { command, .. } => write!(f, "command {command}"),
+            Requirement::Executable(executable) => {
+                write!(f, "executable {}", executable.executable)?;
+                if let Some(version) = &executable.version {
+                    write!(f, " {version}")?;
+                }
+                Ok(())
+            }
+            Requirement::OutputPattern { pattern, .. } => write!(f, "output matching '{pattern}'"),
+            Requirement::Any { requirements, .. } => {
+                write!(f, "any of {} requirements", requirements.len())
+            }
+            Requirement::All { requirements, .. } => {
+                write!(f, "all of {} requirements", requirements.len())
+            }
+            Requirement::Not { requirement, .. } => write!(f, "not {requirement}"),
+        }
     }
 }
+
+/// An HTTP request sent over a Unix domain socket, as served by daemons such as Docker.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UnixSocketHttp {
+    /// Path of the request, e.g. `/_ping`
+    #[serde(default = "default_request_path")]
+    pub path: String,
+    #[serde(default)]
+    pub method: HttpMethod,
+    /// Accepted status codes, defaults to any 2xx status
+    #[serde(default)]
+    pub status: HttpStatusMatcher,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Regex that the response body has to match
+    #[serde(default)]
+    pub body_pattern: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ExecutableRequirement {
+    /// Name of an executable on `PATH`, or a path to one, relative to the workdir of the service
+    pub executable: String,
+    /// Semver requirement that the first version number in the output of the version command has to match, e.g.
+    /// `>=17, <22`
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Regex that the output of the version command has to match
+    #[serde(default)]
+    pub version_pattern: Option<String>,
+    /// Arguments that make the executable print its version
+    #[serde(default = "default_version_args")]
+    pub version_args: Vec<String>,
+    /// Time after which the version command is killed and the check fails
+    #[serde(default = "default_command_timeout", with = "humantime_serde")]
+    pub timeout: Duration,
+}
+
+/// How a port requirement determines whether the port is up.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PortCheckMode {
+    /// The port counts as up if binding to it fails, meaning any process listening on it satisfies the check
+    #[default]
+    #[serde(rename = "bind")]
+    Bind,
+    /// The port counts as up if a TCP connection to it can be established, trying all addresses the host resolves to
+    #[serde(rename = "connect")]
+    Connect,
+}
+
+fn default_connect_timeout() -> Duration {
+    Duration::from_secs(1)
+}
+
+fn default_request_path() -> String {
+    "/".to_string()
+}
+
+fn default_command_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_version_args() -> Vec<String> {
+    vec!["--version".to_string()]
+}
+
+fn default_branch_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_exit_codes() -> Vec<i32> {
+    vec![0]
+}
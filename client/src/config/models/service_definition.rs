@@ -1,8 +1,12 @@
+use std::collections::HashMap;
 use std::time::Duration;
 
 use serde_derive::{Deserialize, Serialize};
 
-use crate::config::{AutomationDefinition, ExecutableEntry, Requirement};
+use crate::config::{
+    AutomationDefinition, ExecutableEntry, HighlightRule, LogFileConfig, ProblemMatcherConfig,
+    Requirement, SequenceCommand, ServiceRetention, SourceOutputConfig,
+};
 use derive_more::Display;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -14,6 +18,8 @@ pub struct ServiceDefinition {
     pub automation: Vec<AutomationDefinition>,
     #[serde(default = "Vec::new")]
     pub tasks: Vec<TaskDefinition>,
+    #[serde(default)]
+    pub output: OutputConfig,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Display, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -40,6 +46,9 @@ pub struct Block {
     pub work: WorkDefinition,
     pub resource_group: Option<String>,
     pub fingerprint: Option<Fingerprint>,
+    /// Matchers extracting problems, such as compiler errors, from the output of the block
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcherConfig>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -68,6 +77,51 @@ pub struct HealthCheckConfig {
     #[serde(default, with = "humantime_serde")]
     pub timeout: Duration,
     pub requirements: Vec<Requirement>,
+    /// If set, requirements keep being checked periodically once the block is healthy
+    #[serde(default)]
+    pub liveness: Option<LivenessConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LivenessConfig {
+    /// Requirements to check, defaults to the health requirements of the block
+    #[serde(default)]
+    pub requirements: Option<Vec<Requirement>>,
+    /// Time between the end of one round of checks and the start of the next
+    #[serde(default = "default_liveness_interval", with = "humantime_serde")]
+    pub interval: Duration,
+    /// Number of consecutive failed rounds after which the block is considered unhealthy
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: usize,
+    /// Re-runs the block once it is considered unhealthy
+    #[serde(default)]
+    pub restart: bool,
+}
+
+fn default_liveness_interval() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_failure_threshold() -> usize {
+    3
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OutputConfig {
+    /// Highlight rules for the output of this service. Evaluated before the globally defined rules.
+    #[serde(default)]
+    pub highlight_rules: Vec<HighlightRule>,
+    /// If set, the output of this service is additionally appended to rotating log files
+    #[serde(default)]
+    pub log_file: Option<LogFileConfig>,
+    /// Overrides of the global output retention limits for this service or individual sources of it
+    #[serde(default)]
+    pub retention: ServiceRetention,
+    /// Settings for individual sources of this service, by source name
+    #[serde(default)]
+    pub sources: HashMap<String, SourceOutputConfig>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -80,7 +134,7 @@ pub struct StatusLine {
 #[serde(tag = "type", deny_unknown_fields)]
 pub enum WorkDefinition {
     #[serde(rename = "cmd-seq")]
-    CommandSeq { commands: Vec<ExecutableEntry> },
+    CommandSeq { commands: Vec<SequenceCommand> },
     #[serde(rename = "process")]
     Process { command: ExecutableEntry },
 }
@@ -98,10 +152,7 @@ pub struct TaskDefinition {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged, deny_unknown_fields)]
 pub enum TaskStep {
-    Command {
-        #[serde(flatten)]
-        command: ExecutableEntry,
-    },
+    Command(SequenceCommand),
     Action {
         action: String,
     },
@@ -0,0 +1,32 @@
+use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+static NUMBERS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+").unwrap());
+
+/// Determines which consecutive lines of the same source are collapsed into a single line with a repeat counter.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CollapseRepeats {
+    /// Every line is kept
+    #[default]
+    #[serde(rename = "off")]
+    Off,
+    /// Identical lines are collapsed
+    #[serde(rename = "exact")]
+    Exact,
+    /// Lines that are identical apart from numbers, such as timestamps or counters, are collapsed
+    #[serde(rename = "ignore_numbers")]
+    IgnoreNumbers,
+}
+impl CollapseRepeats {
+    pub fn is_repeat(&self, previous: &str, line: &str) -> bool {
+        match self {
+            CollapseRepeats::Off => false,
+            CollapseRepeats::Exact => previous == line,
+            CollapseRepeats::IgnoreNumbers => {
+                previous == line
+                    || NUMBERS.replace_all(previous, "#") == NUMBERS.replace_all(line, "#")
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputExportSettings {
+    pub format: ExportFormat,
+    /// Maximum number of lines to export, counted from the end of the output with collapsed repeats counted as separate
+    /// lines. Exports all buffered lines if not set.
+    pub max_lines: Option<usize>,
+    /// If set, exports from the output pane only include the lines passing the current filter and log level
+    pub apply_filter: bool,
+    pub timestamps: bool,
+    /// File to export to. Relative paths are resolved against the data directory. The placeholders `{timestamp}` and
+    /// `{extension}` are replaced by the time of the export and the extension of the format. Existing files are not
+    /// overwritten. If not set, a timestamped file is created in the `exports` directory within the data directory.
+    pub path: Option<String>,
+}
+
+impl Default for OutputExportSettings {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Text,
+            max_lines: None,
+            apply_filter: true,
+            timestamps: true,
+            path: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Plain text, one line per output line prefixed with its timestamp and source
+    #[serde(rename = "text")]
+    Text,
+    /// One JSON object per output line
+    #[serde(rename = "jsonl")]
+    Jsonl,
+}
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Text => "log",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use derive_more::Display;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::ColorWrapper;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HighlightRule {
+    /// Regular expression matched against each output line. The first matching rule determines the level and color
+    /// of the line.
+    pub pattern: String,
+    /// Log level assigned to matching lines
+    #[serde(default)]
+    pub level: Option<LogLevel>,
+    /// Color used for matching lines. If not set, the theme color of the assigned level is used instead. Rules only set
+    /// the text color, other styles such as bold text aren't supported.
+    #[serde(default)]
+    pub color: Option<ColorWrapper>,
+}
+
+#[derive(
+    Serialize, Deserialize, Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub enum LogLevel {
+    #[serde(rename = "debug")]
+    Debug,
+    #[serde(rename = "info")]
+    Info,
+    #[serde(rename = "warn")]
+    Warn,
+    #[serde(rename = "error")]
+    Error,
+}
@@ -0,0 +1,31 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// Configuration for persisting the output of a service into rotating log files.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LogFileConfig {
+    /// Names of the sources to persist. Persists the output of all sources if not set.
+    #[serde(default)]
+    pub sources: Option<Vec<String>>,
+    /// Path of the log file. Relative paths are resolved against the data directory. Defaults to
+    /// `logs/<service id>.log`.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Size in bytes after which the log file is rotated
+    #[serde(default = "default_max_size")]
+    pub max_size: u64,
+    /// Number of rotated files to keep in addition to the current file
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+    /// If set, rotated files are compressed with gzip
+    #[serde(default)]
+    pub compress: bool,
+}
+
+fn default_max_size() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_max_files() -> usize {
+    5
+}
@@ -0,0 +1,44 @@
+mod collapse_repeats;
+mod export_settings;
+mod highlight_rule;
+mod log_file;
+mod problem_matcher;
+mod retention;
+mod source_config;
+
+pub use collapse_repeats::*;
+pub use export_settings::*;
+pub use highlight_rule::*;
+pub use log_file::*;
+pub use problem_matcher::*;
+pub use retention::*;
+pub use source_config::*;
+
+use macros::PartialStruct;
+
+#[derive(Debug, Clone, PartialStruct)]
+pub struct OutputSettings {
+    /// Rules for detecting the log level of output lines and highlighting them. These apply to the output of all
+    /// services, but any rules defined by a service itself take precedence.
+    pub highlight_rules: Vec<HighlightRule>,
+    /// Defaults for exporting output to a file
+    pub export: OutputExportSettings,
+    /// Limits on the output kept in memory per source. Services can override these.
+    pub retention: OutputRetention,
+    /// If set, scrolling down to the newest line resumes following the output
+    pub auto_resume_follow: bool,
+    /// Which consecutive lines of the same source are collapsed into a single line with a repeat counter
+    pub collapse_repeats: CollapseRepeats,
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        Self {
+            highlight_rules: Vec::new(),
+            export: OutputExportSettings::default(),
+            retention: OutputRetention::default(),
+            auto_resume_follow: true,
+            collapse_repeats: CollapseRepeats::default(),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use derive_more::Display;
+use serde_derive::{Deserialize, Serialize};
+
+/// Problem matcher of a block, either one of the built-in presets or a custom one.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ProblemMatcherConfig {
+    Preset(ProblemMatcherPreset),
+    Custom(ProblemMatcher),
+}
+impl ProblemMatcherConfig {
+    pub fn resolve(&self) -> ProblemMatcher {
+        match self {
+            ProblemMatcherConfig::Preset(preset) => preset.matcher(),
+            ProblemMatcherConfig::Custom(matcher) => matcher.clone(),
+        }
+    }
+}
+
+/// Extracts diagnostics, such as compiler errors, from the output of a block.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProblemMatcher {
+    /// Regular expression matched against each output line. The named capture groups `file`, `line`, `column`,
+    /// `severity` and `message` are extracted from matching lines, all of them being optional.
+    pub pattern: String,
+    /// Regular expression for the location of a problem, for tools that print it on a separate line following the
+    /// message. If set, a problem is only reported once a line matching this follows within a few lines, and the
+    /// `file`, `line` and `column` groups are extracted from that line instead.
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Regular expression for lines that match the pattern without being a problem, such as the summary a tool prints
+    /// after its problems.
+    #[serde(default)]
+    pub exclude: Option<String>,
+    /// Severity of problems for which the pattern doesn't capture one
+    #[serde(default)]
+    pub severity: Option<ProblemSeverity>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemMatcherPreset {
+    #[serde(rename = "rustc")]
+    Rustc,
+    #[serde(rename = "tsc")]
+    Tsc,
+    #[serde(rename = "javac")]
+    Javac,
+    #[serde(rename = "gcc")]
+    Gcc,
+}
+impl ProblemMatcherPreset {
+    pub fn matcher(&self) -> ProblemMatcher {
+        let (pattern, location, exclude) = match self {
+            ProblemMatcherPreset::Rustc => (
+                r"^(?P<severity>error|warning)(\[\w+\])?: (?P<message>.+)$",
+                Some(r"^\s*--> (?P<file>[^:]+):(?P<line>\d+):(?P<column>\d+)$"),
+                // Summaries printed by rustc and cargo once compilation has finished
+                Some(
+                    r"^(error: could not compile|error: aborting due to|warning: build failed, waiting|warning: .+ generated \d+ warnings?)",
+                ),
+            ),
+            ProblemMatcherPreset::Tsc => (
+                r"^(?P<file>[^\s(]+)\((?P<line>\d+),(?P<column>\d+)\): (?P<severity>error|warning) (?P<message>TS\d+: .+)$",
+                None,
+                None,
+            ),
+            ProblemMatcherPreset::Javac => (
+                r"^(?P<file>.+\.java):(?P<line>\d+): (?P<severity>error|warning): (?P<message>.+)$",
+                None,
+                None,
+            ),
+            ProblemMatcherPreset::Gcc => (
+                r"^(?P<file>[^:\s]+):(?P<line>\d+):(?P<column>\d+): (?P<severity>fatal error|error|warning|note): (?P<message>.+)$",
+                None,
+                None,
+            ),
+        };
+
+        ProblemMatcher {
+            pattern: pattern.to_string(),
+            location: location.map(str::to_string),
+            exclude: exclude.map(str::to_string),
+            severity: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProblemSeverity {
+    #[serde(rename = "info")]
+    Info,
+    #[serde(rename = "warning")]
+    Warning,
+    #[serde(rename = "error")]
+    Error,
+}
+impl ProblemSeverity {
+    /// Parses the severity as printed by common tools.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.to_lowercase().as_str() {
+            "error" | "fatal error" | "fatal" | "err" => Some(ProblemSeverity::Error),
+            "warning" | "warn" => Some(ProblemSeverity::Warning),
+            "note" | "info" | "help" | "hint" => Some(ProblemSeverity::Info),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Limits on the output kept in memory for each output source. Once a limit is exceeded, the oldest lines of the
+/// source are dropped. The newest line is always kept, even if it exceeds the byte limit by itself.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputRetention {
+    /// Maximum number of lines kept per source
+    pub max_lines: usize,
+    /// Maximum total size in bytes of the lines kept per source. Unlimited if not set.
+    pub max_bytes: Option<u64>,
+}
+
+impl Default for OutputRetention {
+    fn default() -> Self {
+        Self {
+            max_lines: 8096,
+            max_bytes: None,
+        }
+    }
+}
+
+/// Overrides of the global retention limits. Limits that are not set are inherited.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionOverride {
+    pub max_lines: Option<usize>,
+    pub max_bytes: Option<u64>,
+}
+impl RetentionOverride {
+    pub fn apply_to(&self, base: &mut OutputRetention) {
+        if let Some(max_lines) = self.max_lines {
+            base.max_lines = max_lines;
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            base.max_bytes = Some(max_bytes);
+        }
+    }
+}
+
+/// Retention limits of a service, overriding the global limits for all of its sources or for individual ones.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServiceRetention {
+    pub max_lines: Option<usize>,
+    pub max_bytes: Option<u64>,
+    /// Overrides for individual sources, by source name. These take precedence over the limits of the service.
+    pub sources: HashMap<String, RetentionOverride>,
+}
+impl ServiceRetention {
+    /// Resolves the limits of the given source of the service, starting from the global limits.
+    pub fn resolve(&self, global: &OutputRetention, source_name: &str) -> OutputRetention {
+        let mut retention = *global;
+        RetentionOverride {
+            max_lines: self.max_lines,
+            max_bytes: self.max_bytes,
+        }
+        .apply_to(&mut retention);
+        if let Some(source) = self.sources.get(source_name) {
+            source.apply_to(&mut retention);
+        }
+
+        retention
+    }
+}
@@ -0,0 +1,20 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// Settings for the output of an individual source of a service.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct SourceOutputConfig {
+    pub format: SourceFormat,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SourceFormat {
+    /// Lines are displayed as they are
+    #[default]
+    #[serde(rename = "plain")]
+    Plain,
+    /// Lines are parsed as JSON objects and displayed as a summary of their level, time, message and remaining fields.
+    /// Lines that aren't JSON objects are displayed as they are.
+    #[serde(rename = "json")]
+    Json,
+}
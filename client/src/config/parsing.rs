@@ -1,17 +1,23 @@
 use Vec;
 use itertools::Itertools;
 use log::{debug, info};
+use regex::Regex;
+use semver::VersionReq;
 use serde::Deserialize;
 use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::fs::{File, read_to_string};
 use std::io::BufReader;
+use std::iter;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 use crate::config::models::{Config, ProfileDefinition, ServiceDefinition};
-use crate::config::{BlockId, PartialSettings, Settings};
+use crate::config::{
+    BlockId, HighlightRule, PartialSettings, Requirement, ServiceRetention, Settings, TaskStep,
+    WorkDefinition,
+};
 
 #[derive(Debug)]
 pub struct ConfigurationError {
@@ -96,6 +102,14 @@ pub fn read_config(dir: &str) -> Result<Config, ConfigurationError> {
         })?;
     }
 
+    validate_highlight_rules(&settings.output.highlight_rules)?;
+    if settings.output.retention.max_lines == 0 {
+        return Err(ConfigurationError {
+            filename: None,
+            msg: "Output retention max_lines must be greater than 0".to_string(),
+        });
+    }
+
     let duplicate_service_ids: Vec<String> = services
         .iter()
         .chunk_by(|service| &service.id)
@@ -162,11 +176,229 @@ fn validate_service_definition(
             });
         }
         used_block_ids.insert(block.id.clone());
+
+        for matcher in block
+            .problem_matchers
+            .iter()
+            .map(|matcher| matcher.resolve())
+        {
+            for pattern in iter::once(&matcher.pattern)
+                .chain(matcher.location.as_ref())
+                .chain(matcher.exclude.as_ref())
+            {
+                if let Err(error) = Regex::new(pattern) {
+                    return Err(ConfigurationError {
+                        filename: None,
+                        msg: format!(
+                            "Invalid problem matcher pattern '{pattern}' in block {block_id}: {error}",
+                            block_id = block.id.inner()
+                        ),
+                    });
+                }
+            }
+        }
+
+        if let Some(liveness) = &block.health.liveness
+            && liveness.failure_threshold == 0
+        {
+            return Err(ConfigurationError {
+                filename: None,
+                msg: format!(
+                    "Liveness failure_threshold of block {block_id} must be greater than 0",
+                    block_id = block.id.inner()
+                ),
+            });
+        }
+    }
+
+    let block_requirements = service.blocks.iter().flat_map(|block| {
+        block
+            .health
+            .requirements
+            .iter()
+            .chain(block.prerequisites.iter())
+            .chain(
+                block
+                    .health
+                    .liveness
+                    .iter()
+                    .flat_map(|liveness| liveness.requirements.iter().flatten()),
+            )
+            .map(|requirement| (format!("block {}", block.id.inner()), requirement))
+    });
+    let task_requirements = service.tasks.iter().flat_map(|task| {
+        task.steps.iter().filter_map(|step| match step {
+            TaskStep::Wait { requirement, .. } => Some((format!("task {}", task.id), requirement)),
+            _ => None,
+        })
+    });
+    for (location, requirement) in block_requirements.chain(task_requirements) {
+        validate_requirement(requirement).map_err(|msg| ConfigurationError {
+            filename: None,
+            msg: format!("{msg} in a requirement of {location}"),
+        })?;
     }
 
+    let block_commands = service.blocks.iter().flat_map(|block| match &block.work {
+        WorkDefinition::CommandSeq { commands } => commands
+            .iter()
+            .map(|command| (format!("block {}", block.id.inner()), command))
+            .collect(),
+        WorkDefinition::Process { .. } => Vec::new(),
+    });
+    let task_commands = service.tasks.iter().flat_map(|task| {
+        task.steps.iter().filter_map(|step| match step {
+            TaskStep::Command(command) => Some((format!("task {}", task.id), command)),
+            _ => None,
+        })
+    });
+    for (location, command) in block_commands.chain(task_commands) {
+        if command.exit_codes.is_empty() {
+            return Err(ConfigurationError {
+                filename: None,
+                msg: format!(
+                    "Command {entry} of {location} has no accepted exit codes",
+                    entry = command.command
+                ),
+            });
+        }
+    }
+
+    validate_highlight_rules(&service.output.highlight_rules)?;
+    if let Some(log_file) = &service.output.log_file
+        && log_file.max_size == 0
+    {
+        return Err(ConfigurationError {
+            filename: None,
+            msg: format!(
+                "Log file max_size of service {service_id} must be greater than 0",
+                service_id = service.id.inner()
+            ),
+        });
+    }
+    validate_service_retention(&service.output.retention).map_err(|msg| ConfigurationError {
+        filename: None,
+        msg: format!(
+            "{msg} in the output retention of service {service_id}",
+            service_id = service.id.inner()
+        ),
+    })?;
+
     Ok(service)
 }
 
+fn validate_requirement(requirement: &Requirement) -> Result<(), String> {
+    match requirement {
+        Requirement::OutputPattern {
+            pattern,
+            failure_pattern,
+            ..
+        } => {
+            for pattern in iter::once(pattern).chain(failure_pattern) {
+                if let Err(error) = Regex::new(pattern) {
+                    return Err(format!("Invalid output pattern '{pattern}': {error}"));
+                }
+            }
+        }
+        Requirement::Http(http) => {
+            if let Some(entry) = http
+                .status
+                .entries()
+                .iter()
+                .find(|entry| entry.range().is_none())
+            {
+                return Err(format!("Invalid HTTP status '{entry}'"));
+            }
+            if let Some(pattern) = &http.body_pattern
+                && let Err(error) = Regex::new(pattern)
+            {
+                return Err(format!("Invalid body pattern '{pattern}': {error}"));
+            }
+            if let Some(pointer) = http
+                .json
+                .keys()
+                .find(|pointer| !pointer.is_empty() && !pointer.starts_with('/'))
+            {
+                return Err(format!(
+                    "Invalid JSON pointer '{pointer}', pointers have to start with '/'"
+                ));
+            }
+        }
+        Requirement::UnixSocket {
+            http: Some(http), ..
+        } => {
+            if let Some(entry) = http
+                .status
+                .entries()
+                .iter()
+                .find(|entry| entry.range().is_none())
+            {
+                return Err(format!("Invalid HTTP status '{entry}'"));
+            }
+            if let Some(pattern) = &http.body_pattern
+                && let Err(error) = Regex::new(pattern)
+            {
+                return Err(format!("Invalid body pattern '{pattern}': {error}"));
+            }
+        }
+        Requirement::Executable(executable) => {
+            if let Some(version) = &executable.version
+                && let Err(error) = VersionReq::parse(version)
+            {
+                return Err(format!("Invalid version requirement '{version}': {error}"));
+            }
+            if let Some(pattern) = &executable.version_pattern
+                && let Err(error) = Regex::new(pattern)
+            {
+                return Err(format!("Invalid version pattern '{pattern}': {error}"));
+            }
+        }
+        Requirement::Any { requirements, .. } | Requirement::All { requirements, .. } => {
+            if requirements.is_empty() {
+                return Err("Combined requirements need at least one requirement".to_string());
+            }
+            for requirement in requirements {
+                validate_requirement(requirement)?;
+            }
+        }
+        Requirement::Not { requirement, .. } => validate_requirement(requirement)?,
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn validate_service_retention(retention: &ServiceRetention) -> Result<(), String> {
+    let line_limits = retention
+        .sources
+        .values()
+        .map(|source| source.max_lines)
+        .chain([retention.max_lines]);
+    for max_lines in line_limits.flatten() {
+        if max_lines == 0 {
+            return Err("max_lines must be greater than 0".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_highlight_rules(rules: &[HighlightRule]) -> Result<(), ConfigurationError> {
+    for rule in rules {
+        if let Err(error) = Regex::new(&rule.pattern) {
+            return Err(ConfigurationError {
+                filename: None,
+                msg: format!(
+                    "Invalid highlight rule pattern '{pattern}': {error}",
+                    pattern = rule.pattern
+                ),
+            });
+        }
+    }
+
+    Ok(())
+}
+
 fn find_first_config_file<P: AsRef<Path>>(path: P) -> Result<PathBuf, ConfigurationError> {
     let extensions = ["toml", "yml", "yaml"];
     let mut path_with_ext: PathBuf = path.as_ref().to_path_buf();
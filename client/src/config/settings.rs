@@ -3,16 +3,21 @@ use serde_derive::Deserialize;
 use crate::config::keybinds::Keybinds;
 use crate::config::keybinds::PartialKeybinds;
 use crate::config::theme::Theme;
-use crate::config::{PartialTheme, ProfileDefinition, ServiceDefinition};
+use crate::config::{
+    OutputSettings, PartialOutputSettings, PartialTheme, ProfileDefinition, ServiceDefinition,
+};
 
 #[derive(Debug, Clone, Default)]
 pub struct Settings {
     pub autolaunch_profile: Option<String>,
     pub theme: Theme,
     pub keybinds: Keybinds,
+    pub output: OutputSettings,
     pub log_file: Option<String>,
     /// Directory for persisting application state across runs.
     pub data_dir: Option<String>,
+    /// Command for opening files referenced in the output, such as `code --goto {file}:{line}:{column}`.
+    pub editor_command: Option<String>,
 }
 impl From<Vec<PartialSettings>> for Settings {
     fn from(mut value: Vec<PartialSettings>) -> Self {
@@ -38,15 +43,22 @@ pub struct PartialSettings {
     /// Directory for persisting application state across runs.
     #[serde(default)]
     pub data_dir: Option<String>,
+    /// Command for opening files referenced in the output. The placeholders `{file}`, `{line}` and `{column}` are
+    /// replaced with the referenced position. Defaults to `$VISUAL` or `$EDITOR`, invoked as `<editor> +{line} {file}`.
+    #[serde(default)]
+    pub editor_command: Option<String>,
     #[serde(default)]
     pub theme: PartialTheme,
     #[serde(default)]
     pub keybinds: PartialKeybinds,
+    #[serde(default)]
+    pub output: PartialOutputSettings,
 }
 impl PartialSettings {
     pub fn apply_to(self, settings: &mut Settings) {
         self.theme.apply_to(&mut settings.theme);
         self.keybinds.apply_to(&mut settings.keybinds);
+        self.output.apply_to(&mut settings.output);
         if let Some(autolaunch_profile) = self.autolaunch_profile {
             settings.autolaunch_profile = Some(autolaunch_profile);
         }
@@ -56,6 +68,9 @@ impl PartialSettings {
         if let Some(data_dir) = self.data_dir {
             settings.data_dir = Some(data_dir);
         }
+        if let Some(editor_command) = self.editor_command {
+            settings.editor_command = Some(editor_command);
+        }
     }
 }
 
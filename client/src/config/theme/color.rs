@@ -1,6 +1,6 @@
 use ratatui::style::Color;
 use serde::de::Visitor;
-use serde::{Deserialize, Deserializer, de};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 use std::fmt;
 use std::ops::Deref;
 
@@ -63,3 +63,15 @@ impl<'de> Deserialize<'de> for ColorWrapper {
         deserializer.deserialize_str(ColorVisitor)
     }
 }
+
+impl Serialize for ColorWrapper {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            Color::Rgb(r, g, b) => serializer.serialize_str(&format!("#{r:02x}{g:02x}{b:02x}")),
+            other => serializer.serialize_str(&other.to_string()),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 mod color;
 
-use crate::config::theme::color::ColorWrapper;
+pub use color::ColorWrapper;
+
+use crate::config::LogLevel;
 use macros::PartialStruct;
 use ratatui::prelude::Color;
 
@@ -17,6 +19,12 @@ pub struct Theme {
     pub idle_color: ColorWrapper,
     pub focused_element: ColorWrapper,
     pub unfocused_element: ColorWrapper,
+    pub log_error_color: ColorWrapper,
+    pub log_warn_color: ColorWrapper,
+    pub log_info_color: ColorWrapper,
+    pub log_debug_color: ColorWrapper,
+    pub search_match_color: ColorWrapper,
+    pub timestamp_color: ColorWrapper,
 }
 
 impl Default for Theme {
@@ -33,6 +41,23 @@ impl Default for Theme {
             idle_color: Color::White.into(),
             focused_element: Color::Rgb(180, 180, 0).into(),
             unfocused_element: Color::Rgb(100, 100, 0).into(),
+            log_error_color: Color::Rgb(220, 50, 50).into(),
+            log_warn_color: Color::Rgb(230, 180, 0).into(),
+            log_info_color: Color::White.into(),
+            log_debug_color: Color::Rgb(120, 120, 120).into(),
+            search_match_color: Color::Rgb(230, 180, 0).into(),
+            timestamp_color: Color::Rgb(120, 120, 120).into(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn log_level_color(&self, level: LogLevel) -> Color {
+        match level {
+            LogLevel::Error => self.log_error_color.0,
+            LogLevel::Warn => self.log_warn_color.0,
+            LogLevel::Info => self.log_info_color.0,
+            LogLevel::Debug => self.log_debug_color.0,
         }
     }
 }
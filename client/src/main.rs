@@ -1,3 +1,4 @@
+use std::io::{self, Stdout};
 use std::sync::{Arc, RwLock};
 use std::time::Instant;
 use std::{env, error::Error, io::stdout, process, thread, time::Duration};
@@ -13,6 +14,8 @@ use log::{LevelFilter, debug, error, info};
 use ratatui::{Terminal, backend::CrosstermBackend};
 
 use crate::runner::file_watcher::FileWatcher;
+use crate::runner::log_sink_writer::LogSinkWriter;
+use crate::runner::output_collector::OutputCollector;
 use crate::runner::service_worker::ServiceWorker;
 use crate::system_state::SystemState;
 use crate::ui::ViewRoot;
@@ -107,11 +110,15 @@ fn main() -> Result<(), Box<dyn Error>> {
         rhai_executor.clone(),
     ));
     let file_watcher = Arc::new(FileWatcher::new(system_state.clone()));
+    let log_sink_writer = Arc::new(LogSinkWriter::new(system_state.clone()));
+    let output_collector = Arc::new(OutputCollector::new(system_state.clone()));
 
     let mut handles = vec![
         ("file-watcher".into(), file_watcher.start()),
         ("rhai-executor".into(), rhai_executor.start()),
         ("service-worker".into(), service_worker.start()),
+        ("log-sink-writer".into(), log_sink_writer.start()),
+        ("output-collector".into(), output_collector.start()),
     ];
 
     system_state
@@ -195,7 +202,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 
         if let Some(selection) = autolaunch_profile {
             info!("Autolaunching profile: {}", selection);
-            system.select_profile(&selection);
+            system.start_preflight(&selection);
         }
     }
 
@@ -231,6 +238,11 @@ fn main() -> Result<(), Box<dyn Error>> {
             }
         }
 
+        let editor_request = system_state.write().unwrap().editor_request.take();
+        if let Some(command) = editor_request {
+            run_in_foreground(&mut terminal, &command)?;
+        }
+
         if system_state.read().unwrap().should_exit {
             break;
         } else {
@@ -242,6 +254,8 @@ fn main() -> Result<(), Box<dyn Error>> {
     file_watcher.stop();
     service_worker.stop();
     rhai_executor.stop();
+    output_collector.stop();
+    log_sink_writer.stop();
 
     match join_threads.join() {
         Ok(_) => info!("Threads joined successfully"),
@@ -262,3 +276,28 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Runs a command that takes over the terminal, such as an editor. The UI is suspended until the command exits.
+fn run_in_foreground(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    command: &[String],
+) -> io::Result<()> {
+    let Some((program, args)) = command.split_first() else {
+        return Ok(());
+    };
+    info!("Running {command:?} in the foreground");
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    match process::Command::new(program).args(args).status() {
+        Ok(status) if !status.success() => error!("Command {command:?} exited with {status}"),
+        Ok(_) => {}
+        Err(error) => error!("Failed to run {command:?}: {error}"),
+    }
+
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    terminal.clear()
+}
@@ -0,0 +1,143 @@
+use crate::utils::resolve_path;
+use regex::Regex;
+use std::env;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+/// Matches references such as `src/main.rs:42:5` or `src/main.rs:42`
+static COLON_REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|[^\w.\-/\\@~+:])(?P<file>(?:[A-Za-z]:)?[\w.\-/\\@~+]*\w\.[A-Za-z]\w*):(?P<line>\d+)(?::(?P<column>\d+))?",
+    )
+    .unwrap()
+});
+/// Matches references such as `src/index.ts(42,5)`, as printed by tsc and MSVC
+static PAREN_REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|[^\w.\-/\\@~+:])(?P<file>(?:[A-Za-z]:)?[\w.\-/\\@~+]*\w\.[A-Za-z]\w*)\((?P<line>\d+)(?:,(?P<column>\d+))?\)",
+    )
+    .unwrap()
+});
+
+/// A reference to a position in a file, as found in output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileReference {
+    pub path: PathBuf,
+    pub line: u32,
+    pub column: Option<u32>,
+}
+impl FileReference {
+    /// Finds the first file reference in a line of output. Relative paths are resolved against the given workdir.
+    pub fn find(text: &str, workdir: &str) -> Option<Self> {
+        let captures = [&COLON_REFERENCE, &PAREN_REFERENCE]
+            .iter()
+            .filter_map(|regex| regex.captures(text))
+            .min_by_key(|captures| captures.name("file").map(|file| file.start()))?;
+
+        Some(Self {
+            path: resolve_path(captures.name("file")?.as_str(), workdir),
+            line: captures.name("line")?.as_str().parse().ok()?,
+            column: captures
+                .name("column")
+                .and_then(|column| column.as_str().parse().ok()),
+        })
+    }
+
+    /// Builds the command opening the referenced file. The template may contain the placeholders `{file}`, `{line}`
+    /// and `{column}`. Without a template, `$VISUAL` or `$EDITOR` is invoked as `<editor> +{line} {file}`, which is
+    /// understood by most terminal editors. Returns `None` if no editor is known.
+    pub fn editor_command(&self, template: Option<&str>) -> Option<Vec<String>> {
+        let template = match template {
+            Some(template) => template.to_owned(),
+            None => {
+                let editor = env::var("VISUAL")
+                    .or_else(|_| env::var("EDITOR"))
+                    .ok()
+                    .filter(|editor| !editor.trim().is_empty())?;
+                format!("{editor} +{{line}} {{file}}")
+            }
+        };
+
+        // Placeholders are replaced within each argument, so that paths containing spaces remain a single argument
+        let command: Vec<String> = template
+            .split_whitespace()
+            .map(|arg| {
+                arg.replace("{file}", &self.path.to_string_lossy())
+                    .replace("{line}", &self.line.to_string())
+                    .replace("{column}", &self.column.unwrap_or(1).to_string())
+            })
+            .collect();
+
+        if command.is_empty() {
+            None
+        } else {
+            Some(command)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileReference;
+    use std::path::PathBuf;
+
+    fn reference(path: &str, line: u32, column: Option<u32>) -> Option<FileReference> {
+        Some(FileReference {
+            path: PathBuf::from(path),
+            line,
+            column,
+        })
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(
+            FileReference::find("  --> src/main.rs:42:5", "/work"),
+            reference("/work/src/main.rs", 42, Some(5))
+        );
+        assert_eq!(
+            FileReference::find("/app/server.py:7: warning: unused import", "/work"),
+            reference("/app/server.py", 7, None)
+        );
+        assert_eq!(
+            FileReference::find("at ./lib/util.js:10:2 (called from app.ts:3:1)", "/work"),
+            reference("/work/./lib/util.js", 10, Some(2))
+        );
+        assert_eq!(
+            FileReference::find(
+                "src/index.ts(42,5): error TS2322: Type 'string' is not assignable",
+                "/work"
+            ),
+            reference("/work/src/index.ts", 42, Some(5))
+        );
+        assert_eq!(
+            FileReference::find("Program.cs(12): warning CS0168", "/work"),
+            reference("/work/Program.cs", 12, None)
+        );
+
+        // Times, URLs and plain numbers aren't references
+        assert_eq!(
+            FileReference::find("12:30:45 started on http://localhost:8080", "/work"),
+            None
+        );
+        assert_eq!(FileReference::find("version 1.2:3", "/work"), None);
+    }
+
+    #[test]
+    fn test_editor_command() {
+        let reference = FileReference {
+            path: PathBuf::from("/work/my project/main.rs"),
+            line: 42,
+            column: None,
+        };
+        assert_eq!(
+            reference.editor_command(Some("code --goto {file}:{line}:{column}")),
+            Some(vec![
+                "code".to_string(),
+                "--goto".to_string(),
+                "/work/my project/main.rs:42:1".to_string()
+            ])
+        );
+        assert_eq!(reference.editor_command(Some("  ")), None);
+    }
+}
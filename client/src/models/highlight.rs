@@ -0,0 +1,72 @@
+use crate::config::{Config, HighlightRule, LogLevel, ServiceId};
+use log::error;
+use ratatui::style::Color;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Compiled versions of the highlight rules defined in settings and in service definitions.
+#[derive(Debug, Clone, Default)]
+pub struct HighlightRules {
+    global: Vec<CompiledHighlightRule>,
+    by_service: HashMap<ServiceId, Vec<CompiledHighlightRule>>,
+}
+impl HighlightRules {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            global: compile_rules(&config.settings.output.highlight_rules),
+            by_service: config
+                .services
+                .iter()
+                .map(|service| {
+                    (
+                        service.id.clone(),
+                        compile_rules(&service.output.highlight_rules),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Finds the first rule that matches the given line. Rules defined by the service itself are checked before the
+    /// global ones.
+    pub fn find(
+        &self,
+        service_id: Option<&ServiceId>,
+        line: &str,
+    ) -> Option<&CompiledHighlightRule> {
+        service_id
+            .and_then(|service_id| self.by_service.get(service_id))
+            .into_iter()
+            .flatten()
+            .chain(self.global.iter())
+            .find(|rule| rule.regex.is_match(line))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CompiledHighlightRule {
+    pub regex: Regex,
+    pub level: Option<LogLevel>,
+    pub color: Option<Color>,
+}
+
+fn compile_rules(rules: &[HighlightRule]) -> Vec<CompiledHighlightRule> {
+    rules
+        .iter()
+        .filter_map(|rule| match Regex::new(&rule.pattern) {
+            Ok(regex) => Some(CompiledHighlightRule {
+                regex,
+                level: rule.level,
+                color: rule.color.map(|color| color.0),
+            }),
+            Err(err) => {
+                // Patterns are validated when reading the configuration, so this should not happen
+                error!(
+                    "Skipping invalid highlight pattern '{}': {err}",
+                    rule.pattern
+                );
+                None
+            }
+        })
+        .collect()
+}
@@ -1,11 +1,25 @@
 pub use automation::*;
+pub use file_reference::*;
+pub use highlight::*;
 pub use output::*;
+pub use output_classifier::*;
+pub use output_export::*;
+pub use preflight::*;
+pub use problems::*;
 pub use profile::*;
 pub use service::*;
+pub use structured_log::*;
 pub use task::*;
 
 mod automation;
+mod file_reference;
+mod highlight;
 mod output;
+mod output_classifier;
+mod output_export;
+mod preflight;
+mod problems;
 mod profile;
 mod service;
+mod structured_log;
 mod task;
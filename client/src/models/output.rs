@@ -1,178 +1,230 @@
-use std::collections::{HashMap, VecDeque};
-use std::convert::Into;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
 
-use crate::config::ServiceId;
+use crate::config::{CollapseRepeats, LogLevel, OutputRetention, ServiceId};
+use crate::models::StructuredLine;
 use crate::system_state::SystemState;
+use chrono::{DateTime, Local};
+use itertools::Itertools;
+use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OutputStore {
     pub outputs: HashMap<OutputKey, VecDeque<OutputLine>>,
+    /// Total size in bytes of the stored lines of each key
+    sizes: HashMap<OutputKey, u64>,
     current_idx: u128,
 }
 impl OutputStore {
     pub fn new() -> Self {
         OutputStore {
             outputs: HashMap::new(),
+            sizes: HashMap::new(),
             current_idx: 1,
         }
     }
 
-    pub fn add_output(&mut self, key: &OutputKey, line: String) -> &OutputLine {
+    /// Stores a line of output, dropping the oldest lines of the key once the given retention limits are exceeded.
+    /// A line repeating the previous line of the key is collapsed into it instead, as determined by `collapse`.
+    pub fn add_output(
+        &mut self,
+        key: &OutputKey,
+        line: String,
+        timestamp: DateTime<Local>,
+        details: LineDetails,
+        retention: &OutputRetention,
+        collapse: CollapseRepeats,
+    ) -> &OutputLine {
         if !self.outputs.contains_key(key) {
             self.outputs.insert(key.clone(), VecDeque::new());
         }
         let deque = self.outputs.get_mut(key).unwrap();
-        deque.push_back(OutputLine {
-            value: line,
-            index: self.current_idx,
-        });
-        self.current_idx += 1;
-        // TODO move to a config field
-        if deque.len() > 8096 {
-            deque.pop_front();
+        let size = self.sizes.entry(key.clone()).or_default();
+        *size += line.len() as u64;
+
+        if let Some(previous) = deque.back_mut()
+            && previous.level == details.level
+            && collapse.is_repeat(previous.latest_value(), &line)
+        {
+            let repeats = previous.repeats.get_or_insert_with(|| {
+                Box::new(LineRepeats {
+                    count: 0,
+                    first_timestamp: timestamp,
+                    recent: VecDeque::new(),
+                })
+            });
+            repeats.count += 1;
+            repeats.recent.push_back((timestamp, line));
+            if repeats.recent.len() > MAX_RECENT_REPEATS
+                && let Some((_, dropped)) = repeats.recent.pop_front()
+            {
+                *size -= dropped.len() as u64;
+            }
+        } else {
+            deque.push_back(OutputLine {
+                value: line,
+                index: self.current_idx,
+                timestamp,
+                level: details.level,
+                color: details.color,
+                structured: details.structured,
+                repeats: None,
+            });
+            self.current_idx += 1;
+        }
+
+        while deque.len() > 1
+            && (deque.len() > retention.max_lines
+                || retention
+                    .max_bytes
+                    .is_some_and(|max_bytes| *size > max_bytes))
+        {
+            if let Some(evicted) = deque.pop_front() {
+                *size -= evicted.size();
+            }
         }
 
         deque.iter().last().unwrap()
     }
 
-    pub fn query_lines_from(
+    /// Index that the next added line will have.
+    pub fn next_index(&self) -> u128 {
+        self.current_idx
+    }
+
+    /// Counts the lines received since the line with the index `min_idx` was added at `since`, including the repeats
+    /// collapsed into lines that were added before. Lines for which `filter` returns `false` are not counted.
+    pub fn count_lines_from<F>(
+        &self,
+        min_idx: u128,
+        since: DateTime<Local>,
+        keys: &[&OutputKey],
+        filter: F,
+    ) -> usize
+    where
+        F: Fn(&OutputKey, &OutputLine) -> bool,
+    {
+        self.buckets(keys)
+            .iter()
+            .map(|(key, lines)| {
+                let start = lines.partition_point(|line| line.index < min_idx);
+                // Of the older lines, only the last one can have been repeated since
+                let repeated = start
+                    .checked_sub(1)
+                    .and_then(|previous| lines.get(previous))
+                    .filter(|line| filter(key, line))
+                    .map_or(0, |line| line.repeats_since(since));
+                repeated
+                    + lines
+                        .range(start..)
+                        .filter(|line| filter(key, line))
+                        .map(|line| line.occurrences())
+                        .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Queries at most `num_lines` lines with an index of at least `min_idx` from the given keys, ordered by index.
+    /// Lines for which `filter` returns `false` are skipped and do not count towards the limit.
+    pub fn query_lines_from<F>(
         &self,
         num_lines: usize,
         min_idx: Option<u128>,
         keys: &Vec<&OutputKey>,
-    ) -> Vec<(&OutputKey, &OutputLine)> {
+        filter: F,
+    ) -> Vec<(&OutputKey, &OutputLine)>
+    where
+        F: Fn(&OutputKey, &OutputLine) -> bool,
+    {
         let min_idx = min_idx.unwrap_or(0);
-        let mut result: Vec<(&OutputKey, &OutputLine)> = Vec::with_capacity(num_lines);
-        let mut bucket_indices: HashMap<OutputKey, Option<usize>> = keys
+        let buckets = self.buckets(keys);
+
+        // Merge the buckets by repeatedly taking the oldest of their next lines. Since indices are unique across all
+        // buckets, the heap entries never compare equal on the index.
+        let mut heap: BinaryHeap<Reverse<(u128, usize, usize)>> = buckets
             .iter()
-            .map(|key| (*key, self.outputs.get(key).unwrap()))
-            .map(|(key, lines)| {
-                if lines.is_empty() {
-                    // If the bucket has 0 lines, then there's nothing we could ever return
-                    (key.clone(), None)
-                } else if lines
-                    .iter()
-                    .all(|OutputLine { index, .. }| index < &min_idx)
-                {
-                    (key.clone(), (lines.len() - 1).into())
-                } else {
-                    // Otherwise find the partition point for elements over the given index, and select the first
-                    // index of the last partition
-                    (
-                        key.clone(),
-                        lines
-                            .partition_point(|OutputLine { index, .. }| index < &min_idx)
-                            .into(),
-                    )
-                }
+            .enumerate()
+            .filter_map(|(bucket, (_, lines))| {
+                let position = lines.partition_point(|line| line.index < min_idx);
+                lines
+                    .get(position)
+                    .map(|line| Reverse((line.index, bucket, position)))
             })
             .collect();
 
-        // Loop until the result vec is fully populated, or we run out of lines.
-        while result.len() < num_lines && bucket_indices.iter().any(|(_, value)| value.is_some()) {
-            // Figure out the bucket with the next line
-            let next_bucket = self
-                .outputs
-                .iter()
-                .filter(|(key, _)| keys.contains(key))
-                .min_by_key(|(key, lines)| {
-                    if let Some(cur_idx) = bucket_indices.get(key).unwrap() {
-                        lines.get(*cur_idx).unwrap().index
-                    } else {
-                        u128::MAX
-                    }
-                })
-                .unwrap()
-                .0;
-            let cur_idx = bucket_indices.get(next_bucket).unwrap().unwrap();
-
-            let line_ref = &self.outputs.get(next_bucket).unwrap().get(cur_idx).unwrap();
-            if line_ref.index >= min_idx {
-                // Push the relevant line into the returned results
-                result.push((next_bucket, line_ref));
+        let mut result: Vec<(&OutputKey, &OutputLine)> = Vec::with_capacity(num_lines);
+        while result.len() < num_lines
+            && let Some(Reverse((_, bucket, position))) = heap.pop()
+        {
+            let (key, lines) = buckets[bucket];
+            let line = &lines[position];
+            if filter(key, line) {
+                result.push((key, line));
             }
-            // Update the current index for the bucket
-            if cur_idx < self.outputs.get(next_bucket).unwrap().len() - 1 {
-                *bucket_indices.get_mut(next_bucket).unwrap() = Some(cur_idx + 1);
-            } else {
-                *bucket_indices.get_mut(next_bucket).unwrap() = None;
+            if let Some(next) = lines.get(position + 1) {
+                heap.push(Reverse((next.index, bucket, position + 1)));
             }
         }
 
-        result.into_iter().collect()
+        result
     }
 
-    pub fn query_lines_to(
+    /// Queries at most `num_lines` lines with an index of at most `max_idx` from the given keys, ordered by index.
+    /// Lines for which `filter` returns `false` are skipped and do not count towards the limit.
+    pub fn query_lines_to<F>(
         &self,
         num_lines: usize,
         max_idx: Option<u128>,
         keys: &Vec<&OutputKey>,
-    ) -> Vec<(&OutputKey, &OutputLine)> {
+        filter: F,
+    ) -> Vec<(&OutputKey, &OutputLine)>
+    where
+        F: Fn(&OutputKey, &OutputLine) -> bool,
+    {
         let max_idx = max_idx.unwrap_or(self.current_idx);
-        let mut result: Vec<(&OutputKey, &OutputLine)> = Vec::with_capacity(num_lines);
-        let mut bucket_indices: HashMap<OutputKey, Option<usize>> = keys
+        let buckets = self.buckets(keys);
+
+        // Merge the buckets backwards by repeatedly taking the newest of their previous lines
+        let mut heap: BinaryHeap<(u128, usize, usize)> = buckets
             .iter()
-            .map(|key| (*key, self.outputs.get(key).unwrap()))
-            .map(|(key, lines)| {
-                if lines.is_empty() {
-                    // If the bucket has 0 lines, then there's nothing we could ever return
-                    (key.clone(), None)
-                } else if lines
-                    .iter()
-                    .last()
-                    .map(|OutputLine { index, .. }| index <= &max_idx)
-                    .unwrap_or(false)
-                {
-                    // If all lines have an index lower than the given max index, then the starting index is the length
-                    // of the bucket
-                    (key.clone(), (lines.len() - 1).into())
-                } else {
-                    // Otherwise find the partition point for elements at most the given index, and select the last
-                    // index of the first partition
-                    (
-                        key.clone(),
-                        (lines
-                            .partition_point(|OutputLine { index, .. }| index <= &max_idx)
-                            .saturating_sub(1))
-                        .into(),
-                    )
-                }
+            .enumerate()
+            .filter_map(|(bucket, (_, lines))| {
+                let position = lines
+                    .partition_point(|line| line.index <= max_idx)
+                    .checked_sub(1)?;
+                Some((lines[position].index, bucket, position))
             })
             .collect();
 
-        // Loop until the result vec is fully populated, or we run out of lines.
-        while result.len() < num_lines && bucket_indices.iter().any(|(_, value)| value.is_some()) {
-            // Figure out the bucket with the next line
-            let next_bucket = self
-                .outputs
-                .iter()
-                .filter(|(key, _)| keys.contains(key))
-                .max_by_key(|(key, lines)| {
-                    if let Some(cur_idx) = bucket_indices.get(key).unwrap() {
-                        lines.get(*cur_idx).unwrap().index + 1
-                    } else {
-                        0
-                    }
-                })
-                .unwrap()
-                .0;
-            let cur_idx = bucket_indices.get(next_bucket).unwrap().unwrap();
-
-            let line_ref = &self.outputs.get(next_bucket).unwrap().get(cur_idx).unwrap();
-            if line_ref.index <= max_idx {
-                // Push the relevant line into the returned results
-                result.push((next_bucket, line_ref));
+        let mut result: Vec<(&OutputKey, &OutputLine)> = Vec::with_capacity(num_lines);
+        while result.len() < num_lines
+            && let Some((_, bucket, position)) = heap.pop()
+        {
+            let (key, lines) = buckets[bucket];
+            let line = &lines[position];
+            if filter(key, line) {
+                result.push((key, line));
             }
-            // Update the current index for the bucket
-            if cur_idx > 0 {
-                *bucket_indices.get_mut(next_bucket).unwrap() = Some(cur_idx - 1);
-            } else {
-                *bucket_indices.get_mut(next_bucket).unwrap() = None;
+            if let Some(previous) = position.checked_sub(1) {
+                heap.push((lines[previous].index, bucket, previous));
             }
         }
 
-        result.into_iter().rev().collect()
+        result.reverse();
+        result
+    }
+
+    /// Looks up the lines of the given keys, skipping duplicate keys and keys without any output.
+    fn buckets<'a>(
+        &'a self,
+        keys: &[&OutputKey],
+    ) -> Vec<(&'a OutputKey, &'a VecDeque<OutputLine>)> {
+        keys.iter()
+            .unique()
+            .filter_map(|key| self.outputs.get_key_value(*key))
+            .filter(|(_, lines)| !lines.is_empty())
+            .collect()
     }
 }
 
@@ -195,6 +247,112 @@ pub enum OutputKind {
 pub struct OutputLine {
     pub value: String,
     pub index: u128,
+    pub timestamp: DateTime<Local>,
+    /// Log level of the line as determined by the highlight rules, if any rule matched
+    pub level: Option<LogLevel>,
+    /// Color of the highlight rule that matched the line, if the rule sets one
+    #[serde(skip)]
+    pub color: Option<Color>,
+    /// The line parsed as a JSON object, for sources with the JSON format
+    #[serde(skip)]
+    pub structured: Option<Box<StructuredLine>>,
+    /// Subsequent occurrences of the line that were collapsed into it, if any
+    pub repeats: Option<Box<LineRepeats>>,
+}
+impl OutputLine {
+    /// Number of times the line occurred in a row.
+    pub fn occurrences(&self) -> usize {
+        self.repeats.as_ref().map_or(1, |repeats| repeats.count + 1)
+    }
+
+    /// Value of the most recent occurrence of the line.
+    pub fn latest_value(&self) -> &str {
+        self.repeats
+            .as_ref()
+            .and_then(|repeats| repeats.recent.back())
+            .map_or(&self.value, |(_, value)| value)
+    }
+
+    /// Timestamp of the most recent occurrence of the line.
+    pub fn latest_timestamp(&self) -> DateTime<Local> {
+        self.repeats
+            .as_ref()
+            .and_then(|repeats| repeats.recent.back())
+            .map_or(self.timestamp, |(timestamp, _)| *timestamp)
+    }
+
+    /// The first occurrence of the line followed by the retained recent ones, oldest first.
+    pub fn retained_occurrences(&self) -> impl Iterator<Item = (&DateTime<Local>, &str)> {
+        std::iter::once((&self.timestamp, self.value.as_str())).chain(
+            self.repeats
+                .iter()
+                .flat_map(|repeats| repeats.recent.iter())
+                .map(|(timestamp, value)| (timestamp, value.as_str())),
+        )
+    }
+
+    /// The first and the retained recent occurrences of the line that were received at or after `since`.
+    pub fn occurrences_since(
+        &self,
+        since: DateTime<Local>,
+    ) -> impl Iterator<Item = (&DateTime<Local>, &str)> {
+        self.retained_occurrences()
+            .filter(move |(timestamp, _)| **timestamp >= since)
+    }
+
+    /// Number of repeats of the line received at or after `since`. Occurrences that weren't retained are only counted
+    /// if the first repeat was received since then, as their timestamps aren't known otherwise.
+    pub fn repeats_since(&self, since: DateTime<Local>) -> usize {
+        match &self.repeats {
+            Some(repeats) if repeats.first_timestamp >= since => repeats.count,
+            Some(repeats) => repeats
+                .recent
+                .iter()
+                .filter(|(timestamp, _)| *timestamp >= since)
+                .count(),
+            None => 0,
+        }
+    }
+
+    /// Number of occurrences between the first and the retained recent ones that were not kept.
+    pub fn omitted_occurrences(&self) -> usize {
+        self.repeats
+            .as_ref()
+            .map_or(0, |repeats| repeats.count - repeats.recent.len())
+    }
+
+    /// Size in bytes counted towards the retention limits.
+    fn size(&self) -> u64 {
+        let repeats = self.repeats.as_ref().map_or(0, |repeats| {
+            repeats
+                .recent
+                .iter()
+                .map(|(_, value)| value.len() as u64)
+                .sum()
+        });
+        self.value.len() as u64 + repeats
+    }
+}
+
+/// What is known about a line from its content, determined once before it's stored.
+#[derive(Debug, Clone, Default)]
+pub struct LineDetails {
+    pub level: Option<LogLevel>,
+    pub color: Option<Color>,
+    pub structured: Option<Box<StructuredLine>>,
+}
+
+/// Number of collapsed occurrences of a line that are kept for showing them when expanding it
+const MAX_RECENT_REPEATS: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LineRepeats {
+    /// Number of occurrences after the first one
+    pub count: usize,
+    /// Time at which the second occurrence was received, which is kept even if the occurrence itself isn't
+    pub first_timestamp: DateTime<Local>,
+    /// The most recent of these occurrences, which may differ from the first one in their numbers
+    pub recent: VecDeque<(DateTime<Local>, String)>,
 }
 
 // TODO move elsewhere?
@@ -204,15 +362,316 @@ pub fn get_active_outputs(state: &SystemState) -> Vec<&OutputKey> {
     store
         .outputs
         .keys()
+        .filter(|key| !state.disabled_outputs.contains(key))
         .filter(|key| {
             if let Some(service_id) = key.service_id.as_ref() {
                 state
                     .query_service(service_id, |service| service.output_enabled)
                     .unwrap_or(false)
             } else {
-                // TODO how to disable non-service outputs?
                 true
             }
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{LineDetails, OutputKey, OutputKind, OutputLine, OutputStore};
+    use crate::config::{CollapseRepeats, OutputRetention, ServiceId};
+    use chrono::{Duration, Local};
+    use std::collections::VecDeque;
+    use std::time::Instant;
+
+    fn key(source_name: &str) -> OutputKey {
+        OutputKey {
+            service_id: Some(ServiceId::new("service")),
+            source_name: source_name.to_string(),
+            kind: OutputKind::ExtProcess,
+        }
+    }
+
+    fn add(store: &mut OutputStore, key: &OutputKey, line: &str) {
+        store.add_output(
+            key,
+            line.to_string(),
+            Local::now(),
+            LineDetails::default(),
+            &OutputRetention::default(),
+            CollapseRepeats::Off,
+        );
+    }
+
+    fn values<'a>(lines: &[(&OutputKey, &'a OutputLine)]) -> Vec<&'a str> {
+        lines.iter().map(|(_, line)| line.value.as_str()).collect()
+    }
+
+    /// Creates a store with the lines `a0`, `b1`, `a2`, `b3`, ... interleaved between the keys `a` and `b`.
+    fn interleaved_store(num_lines: usize) -> (OutputStore, OutputKey, OutputKey) {
+        let (a, b) = (key("a"), key("b"));
+        let mut store = OutputStore::new();
+        for i in 0..num_lines {
+            if i % 2 == 0 {
+                add(&mut store, &a, &format!("a{i}"));
+            } else {
+                add(&mut store, &b, &format!("b{i}"));
+            }
+        }
+        (store, a, b)
+    }
+
+    #[test]
+    fn test_query_merges_keys_in_index_order() {
+        let (store, a, b) = interleaved_store(6);
+
+        let lines = store.query_lines_from(10, None, &vec![&a, &b], |_, _| true);
+        assert_eq!(values(&lines), vec!["a0", "b1", "a2", "b3", "a4", "b5"]);
+
+        let lines = store.query_lines_to(10, None, &vec![&b, &a], |_, _| true);
+        assert_eq!(values(&lines), vec!["a0", "b1", "a2", "b3", "a4", "b5"]);
+    }
+
+    #[test]
+    fn test_query_respects_bounds_and_limit() {
+        let (store, a, b) = interleaved_store(6);
+        let keys = vec![&a, &b];
+
+        // Line indices start at 1, so `b3` has the index 4
+        let lines = store.query_lines_from(2, Some(4), &keys, |_, _| true);
+        assert_eq!(values(&lines), vec!["b3", "a4"]);
+
+        let lines = store.query_lines_to(2, Some(4), &keys, |_, _| true);
+        assert_eq!(values(&lines), vec!["a2", "b3"]);
+
+        let lines = store.query_lines_from(2, Some(100), &keys, |_, _| true);
+        assert!(lines.is_empty());
+
+        let lines = store.query_lines_to(0, None, &keys, |_, _| true);
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_query_skips_filtered_lines() {
+        let (store, a, b) = interleaved_store(8);
+        let keys = vec![&a, &b];
+
+        let lines = store.query_lines_from(2, None, &keys, |key, _| key == &b);
+        assert_eq!(values(&lines), vec!["b1", "b3"]);
+
+        let lines = store.query_lines_to(3, None, &keys, |_, line| line.value != "a6");
+        assert_eq!(values(&lines), vec!["a4", "b5", "b7"]);
+    }
+
+    #[test]
+    fn test_query_handles_empty_missing_and_duplicate_keys() {
+        let (mut store, a, b) = interleaved_store(4);
+        let empty = key("empty");
+        let missing = key("missing");
+        store.outputs.insert(empty.clone(), VecDeque::new());
+
+        let keys = vec![&a, &empty, &missing, &b, &a];
+        let lines = store.query_lines_from(10, None, &keys, |_, _| true);
+        assert_eq!(values(&lines), vec!["a0", "b1", "a2", "b3"]);
+
+        let lines = store.query_lines_to(10, None, &keys, |_, _| true);
+        assert_eq!(values(&lines), vec!["a0", "b1", "a2", "b3"]);
+
+        let lines = store.query_lines_to(10, None, &vec![&empty, &missing], |_, _| true);
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_query_with_evicted_indices() {
+        let a = key("a");
+        let mut store = OutputStore::new();
+        let retention = OutputRetention {
+            max_lines: 3,
+            max_bytes: None,
+        };
+        for i in 0..6 {
+            store.add_output(
+                &a,
+                format!("a{i}"),
+                Local::now(),
+                LineDetails::default(),
+                &retention,
+                CollapseRepeats::Off,
+            );
+        }
+        let keys = vec![&a];
+
+        // Lines 1 to 3 have been evicted
+        let lines = store.query_lines_from(2, Some(2), &keys, |_, _| true);
+        assert_eq!(values(&lines), vec!["a3", "a4"]);
+
+        let lines = store.query_lines_to(2, Some(2), &keys, |_, _| true);
+        assert!(lines.is_empty());
+
+        let lines = store.query_lines_to(10, None, &keys, |_, _| true);
+        assert_eq!(values(&lines), vec!["a3", "a4", "a5"]);
+    }
+
+    #[test]
+    fn test_retention_by_bytes_keeps_newest_line() {
+        let a = key("a");
+        let mut store = OutputStore::new();
+        let retention = OutputRetention {
+            max_lines: 100,
+            max_bytes: Some(10),
+        };
+        for line in ["1234", "5678", "90"] {
+            store.add_output(
+                &a,
+                line.to_string(),
+                Local::now(),
+                LineDetails::default(),
+                &retention,
+                CollapseRepeats::Off,
+            );
+        }
+        let lines = store.query_lines_to(10, None, &vec![&a], |_, _| true);
+        assert_eq!(values(&lines), vec!["1234", "5678", "90"]);
+
+        store.add_output(
+            &a,
+            "a".repeat(20),
+            Local::now(),
+            LineDetails::default(),
+            &retention,
+            CollapseRepeats::Off,
+        );
+        let lines = store.query_lines_to(10, None, &vec![&a], |_, _| true);
+        assert_eq!(values(&lines), vec!["a".repeat(20)]);
+    }
+
+    #[test]
+    fn test_collapse_repeats() {
+        let a = key("a");
+        let mut store = OutputStore::new();
+        let retention = OutputRetention::default();
+        for line in ["ping 1", "ping 1", "ping 2", "pong", "pong"] {
+            store.add_output(
+                &a,
+                line.to_string(),
+                Local::now(),
+                LineDetails::default(),
+                &retention,
+                CollapseRepeats::Exact,
+            );
+        }
+        let lines = store.query_lines_to(10, None, &vec![&a], |_, _| true);
+        assert_eq!(values(&lines), vec!["ping 1", "ping 2", "pong"]);
+        assert_eq!(lines[0].1.occurrences(), 2);
+        assert_eq!(lines[1].1.occurrences(), 1);
+        assert_eq!(lines[2].1.occurrences(), 2);
+
+        for i in 0..30 {
+            store.add_output(
+                &a,
+                format!("ping {i}"),
+                Local::now(),
+                LineDetails::default(),
+                &retention,
+                CollapseRepeats::IgnoreNumbers,
+            );
+        }
+        let lines = store.query_lines_to(10, None, &vec![&a], |_, _| true);
+        assert_eq!(values(&lines), vec!["ping 1", "ping 2", "pong", "ping 0"]);
+        let repeats = lines[3].1.repeats.as_ref().unwrap();
+        assert_eq!(repeats.count, 29);
+        assert_eq!(repeats.recent.len(), super::MAX_RECENT_REPEATS);
+        assert_eq!(lines[3].1.latest_value(), "ping 29");
+    }
+
+    #[test]
+    fn test_count_lines_since_pause() {
+        let (a, b) = (key("a"), key("b"));
+        let mut store = OutputStore::new();
+        let start = Local::now();
+        let add_at = |store: &mut OutputStore, key: &OutputKey, line: &str, seconds: i64| {
+            store.add_output(
+                key,
+                line.to_string(),
+                start + Duration::seconds(seconds),
+                LineDetails::default(),
+                &OutputRetention::default(),
+                CollapseRepeats::Exact,
+            );
+        };
+        add_at(&mut store, &a, "waiting", 0);
+        add_at(&mut store, &a, "waiting", 1);
+        add_at(&mut store, &b, "started", 2);
+
+        // Paused after the third second
+        let (paused_at, since) = (store.next_index(), start + Duration::seconds(3));
+        for seconds in 4..20 {
+            add_at(&mut store, &a, "waiting", seconds);
+        }
+        for seconds in 20..50 {
+            add_at(&mut store, &b, "ready", seconds);
+        }
+
+        let keys = vec![&a, &b];
+        // Repeats collapsed into a line from before the pause are counted, as are ones that are no longer retained
+        assert_eq!(
+            store.count_lines_from(paused_at, since, &keys, |_, _| true),
+            46
+        );
+        assert_eq!(
+            store.count_lines_from(paused_at, since, &keys, |key, _| key == &a),
+            16
+        );
+
+        let lines = store.query_lines_to(10, None, &keys, |_, _| true);
+        let waiting = lines[0].1;
+        assert_eq!(waiting.repeats_since(since), 16);
+        assert_eq!(waiting.occurrences_since(since).count(), 16);
+        assert_eq!(
+            waiting
+                .occurrences_since(start + Duration::seconds(19))
+                .count(),
+            1
+        );
+    }
+
+    /// Times queries against many keys with full buffers. Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_query_many_keys() {
+        let keys: Vec<OutputKey> = (0..64).map(|i| key(&format!("source-{i}"))).collect();
+        let mut store = OutputStore::new();
+        for i in 0..(64 * 8096) {
+            add(&mut store, &keys[i % keys.len()], &format!("line {i}"));
+        }
+        let keys: Vec<&OutputKey> = keys.iter().collect();
+
+        for (name, filter) in [
+            (
+                "all lines",
+                (|_, _| true) as fn(&OutputKey, &OutputLine) -> bool,
+            ),
+            ("sparse filter", |_, line| line.index % 97 == 0),
+        ] {
+            let iterations = 200;
+            let started = Instant::now();
+            for _ in 0..iterations {
+                let lines = store.query_lines_to(60, None, &keys, filter);
+                assert_eq!(lines.len(), 60);
+            }
+            println!(
+                "query_lines_to, {name}: {:?} per query",
+                started.elapsed() / iterations
+            );
+
+            let started = Instant::now();
+            for _ in 0..iterations {
+                let lines = store.query_lines_from(60, Some(1000), &keys, filter);
+                assert_eq!(lines.len(), 60);
+            }
+            println!(
+                "query_lines_from, {name}: {:?} per query",
+                started.elapsed() / iterations
+            );
+        }
+    }
+}
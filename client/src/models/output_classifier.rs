@@ -0,0 +1,81 @@
+use crate::config::{BlockId, Config, ServiceId, SourceFormat};
+use crate::models::{
+    HighlightRules, LineDetails, OutputKey, OutputKind, ProblemLineMatch, ProblemMatchers,
+    StructuredLine,
+};
+use std::collections::HashMap;
+
+/// What is known about a line of output from its content alone.
+#[derive(Debug, Clone, Default)]
+pub struct ClassifiedLine {
+    pub details: LineDetails,
+    /// Matches of the problem matchers of the block that wrote the line
+    pub problems: ProblemLineMatch,
+}
+
+/// Classifies lines of output based on the configuration. As this doesn't depend on the rest of the system state, the
+/// threads reading the output of processes classify their lines before locking the system state to add them.
+#[derive(Debug, Clone, Default)]
+pub struct OutputClassifier {
+    pub highlight_rules: HighlightRules,
+    pub problem_matchers: ProblemMatchers,
+    formats: HashMap<(ServiceId, String), SourceFormat>,
+}
+impl OutputClassifier {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            highlight_rules: HighlightRules::new(config),
+            problem_matchers: ProblemMatchers::new(config),
+            formats: config
+                .services
+                .iter()
+                .flat_map(|service| {
+                    service.output.sources.iter().map(|(source_name, source)| {
+                        ((service.id.clone(), source_name.clone()), source.format)
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    pub fn source_format(&self, key: &OutputKey) -> SourceFormat {
+        key.service_id
+            .as_ref()
+            .and_then(|service_id| {
+                self.formats
+                    .get(&(service_id.clone(), key.source_name.clone()))
+            })
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn classify(&self, key: &OutputKey, line: &str) -> ClassifiedLine {
+        let structured = match self.source_format(key) {
+            SourceFormat::Json => StructuredLine::parse(line).map(Box::new),
+            SourceFormat::Plain => None,
+        };
+        let rule = self.highlight_rules.find(key.service_id.as_ref(), line);
+        // The level field of structured lines takes precedence over the highlight rules
+        let level = structured
+            .as_ref()
+            .and_then(|structured| structured.level())
+            .or_else(|| rule.and_then(|rule| rule.level));
+
+        let problems = match (key.kind, key.service_id.as_ref()) {
+            (OutputKind::ExtProcess, Some(service_id)) => {
+                self.problem_matchers
+                    .match_line(service_id, &BlockId::new(&key.source_name), line)
+            }
+            _ => ProblemLineMatch::default(),
+        };
+
+        ClassifiedLine {
+            details: LineDetails {
+                level,
+                color: rule.and_then(|rule| rule.color),
+                structured,
+            },
+            problems,
+        }
+    }
+}
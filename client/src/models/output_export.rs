@@ -0,0 +1,170 @@
+use crate::config::{ExportFormat, LogLevel, OutputExportSettings};
+use crate::models::{OutputKey, OutputKind, OutputLine};
+use crate::system_state::SystemState;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub struct ExportResult {
+    pub path: PathBuf,
+    pub num_lines: usize,
+}
+
+/// Lines collected for an export. They're copied out of the output store, so that the file can be written without
+/// holding on to the system state.
+pub struct OutputExport {
+    pub path: PathBuf,
+    format: ExportFormat,
+    profile_name: String,
+    lines: Vec<ExportedLine>,
+}
+
+#[derive(Serialize)]
+struct ExportedLine {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp: Option<DateTime<Local>>,
+    service: Option<String>,
+    source: String,
+    kind: OutputKind,
+    level: Option<LogLevel>,
+    line: String,
+    /// Number of repeats of the line that followed it but were not retained
+    #[serde(skip_serializing_if = "Option::is_none")]
+    omitted_repeats: Option<usize>,
+}
+
+impl OutputExport {
+    /// Collects the output of the given keys to export to `path`. Collapsed repeats are exported as the separate lines
+    /// they were received as, and count towards the maximum number of lines as such. Lines for which `filter` returns
+    /// `false` are not exported.
+    pub fn collect<F>(
+        state: &SystemState,
+        keys: &Vec<&OutputKey>,
+        settings: &OutputExportSettings,
+        path: PathBuf,
+        filter: F,
+    ) -> Self
+    where
+        F: Fn(&OutputKey, &OutputLine) -> bool,
+    {
+        let buffered_lines: usize = keys
+            .iter()
+            .filter_map(|key| state.output_store.outputs.get(*key))
+            .map(|lines| lines.len())
+            .sum();
+        let stored_lines = state
+            .output_store
+            .query_lines_to(buffered_lines, None, keys, filter);
+        let occurrences: Vec<_> = stored_lines
+            .iter()
+            .flat_map(|(key, line)| {
+                line.retained_occurrences().enumerate().map(
+                    move |(occurrence, (timestamp, value))| {
+                        let omitted = match (occurrence, line.omitted_occurrences()) {
+                            (0, omitted) if omitted > 0 => Some(omitted),
+                            _ => None,
+                        };
+                        (*key, line, timestamp, value, omitted)
+                    },
+                )
+            })
+            .collect();
+        let skipped = settings
+            .max_lines
+            .map_or(0, |max_lines| occurrences.len().saturating_sub(max_lines));
+
+        let lines = occurrences
+            .into_iter()
+            .skip(skipped)
+            .map(|(key, line, timestamp, value, omitted)| ExportedLine {
+                timestamp: settings.timestamps.then_some(*timestamp),
+                service: key.service_id.as_ref().map(|id| id.inner().to_owned()),
+                source: key.source_name.clone(),
+                kind: key.kind,
+                level: line.level,
+                line: value.to_owned(),
+                omitted_repeats: omitted,
+            })
+            .collect();
+
+        Self {
+            path,
+            format: settings.format,
+            profile_name: state.get_profile_name().unwrap_or_default().to_owned(),
+            lines,
+        }
+    }
+
+    /// Writes the collected lines to a new file. Existing files are never overwritten.
+    pub fn write(self) -> std::io::Result<ExportResult> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = File::create_new(&self.path).map_err(|err| match err.kind() {
+            ErrorKind::AlreadyExists => std::io::Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{} already exists", self.path.display()),
+            ),
+            _ => err,
+        })?;
+
+        let mut writer = BufWriter::new(file);
+        for line in &self.lines {
+            match self.format {
+                ExportFormat::Text => {
+                    if let Some(timestamp) = line.timestamp {
+                        write!(writer, "{} ", timestamp.format("%Y-%m-%d %H:%M:%S%.3f"))?;
+                    }
+                    writeln!(
+                        writer,
+                        "{kind}/{name}/{source} | {value}{omitted}",
+                        kind = match line.kind {
+                            OutputKind::System => "i",
+                            OutputKind::ExtProcess => "c",
+                        },
+                        name = line.service.as_deref().unwrap_or(&self.profile_name),
+                        source = line.source,
+                        value = line.line,
+                        omitted = line
+                            .omitted_repeats
+                            .map(|omitted| format!(" ({omitted} similar lines omitted)"))
+                            .unwrap_or_default(),
+                    )?;
+                }
+                ExportFormat::Jsonl => {
+                    serde_json::to_writer(&mut writer, line)?;
+                    writeln!(writer)?;
+                }
+            }
+        }
+        writer.flush()?;
+
+        Ok(ExportResult {
+            path: self.path,
+            num_lines: self.lines.len(),
+        })
+    }
+}
+
+/// Path of the file to export to, with `{timestamp}` in the configured path replaced by the current time.
+pub fn resolve_export_path(state: &SystemState, settings: &OutputExportSettings) -> PathBuf {
+    let data_dir = Path::new(&state.resolved_data_dir);
+    let path = settings
+        .path
+        .as_deref()
+        .unwrap_or(DEFAULT_EXPORT_PATH)
+        .replace(
+            "{timestamp}",
+            &Local::now().format("%Y%m%d-%H%M%S").to_string(),
+        )
+        .replace("{extension}", settings.format.extension());
+    match Path::new(&path) {
+        path if path.is_absolute() => path.to_path_buf(),
+        path => data_dir.join(path),
+    }
+}
+
+const DEFAULT_EXPORT_PATH: &str = "exports/output-{timestamp}.{extension}";
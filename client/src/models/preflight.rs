@@ -0,0 +1,250 @@
+use crate::config::{
+    ExecutableRequirement, ProfileDefinition, Requirement, ServiceDefinition, TaskStep,
+};
+
+/// Checks of the executables required by the services of a profile, performed before the profile is activated so
+/// that missing tools are reported up front rather than as failures to spawn processes.
+#[derive(Debug, Clone)]
+pub struct Preflight {
+    pub profile_id: String,
+    pub checks: Vec<PreflightCheck>,
+}
+impl Preflight {
+    /// Collects the executable requirements of the profile, including those of its services. Executables nested in
+    /// `all` requirements are checked individually, while those in `any` requirements are checked as alternatives, one
+    /// of which has to be found. Requirements nested in `not`, or in `any` requirements that can be met without any
+    /// executable, don't have to be met up front and are left out.
+    pub fn new(profile: &ProfileDefinition, all_services: &[ServiceDefinition]) -> Self {
+        let mut preflight = Preflight {
+            profile_id: profile.id.clone(),
+            checks: Vec::new(),
+        };
+
+        for service in profile.services.iter().filter_map(|service_ref| {
+            all_services
+                .iter()
+                .find(|service| service.id == service_ref.id)
+        }) {
+            for block in &service.blocks {
+                let requirements = block
+                    .prerequisites
+                    .iter()
+                    .chain(block.health.requirements.iter())
+                    .chain(
+                        block
+                            .health
+                            .liveness
+                            .iter()
+                            .flat_map(|liveness| liveness.requirements.iter().flatten()),
+                    );
+                for requirement in requirements {
+                    preflight.add(
+                        requirement,
+                        &service.workdir,
+                        format!("{}/{}", service.id, block.id),
+                    );
+                }
+            }
+            for task in &service.tasks {
+                for requirement in task_requirements(&task.steps) {
+                    preflight.add(
+                        requirement,
+                        &service.workdir,
+                        format!("{}/{}", service.id, task.id),
+                    );
+                }
+            }
+        }
+        for task in &profile.tasks {
+            for requirement in task_requirements(&task.steps) {
+                preflight.add(requirement, &profile.workdir, task.id.to_string());
+            }
+        }
+
+        preflight
+    }
+
+    fn add(&mut self, requirement: &Requirement, workdir: &str, required_by: String) {
+        for alternatives in required_executables(requirement) {
+            // Services often share tools, so identical requirements are only checked once
+            match self.checks.iter_mut().find(|check| {
+                check.alternatives == alternatives
+                    && (check.workdir == workdir
+                        || !alternatives
+                            .iter()
+                            .any(|requirement| requirement.executable.contains('/')))
+            }) {
+                Some(check) => check.required_by.push(required_by.clone()),
+                None => self.checks.push(PreflightCheck {
+                    alternatives,
+                    workdir: workdir.to_owned(),
+                    required_by: vec![required_by.clone()],
+                    status: PreflightStatus::Pending,
+                }),
+            }
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.checks.iter().all(|check| {
+            matches!(
+                check.status,
+                PreflightStatus::Ok(_) | PreflightStatus::Failed(_)
+            )
+        })
+    }
+
+    pub fn is_successful(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| matches!(check.status, PreflightStatus::Ok(_)))
+    }
+}
+
+/// The executables that have to be found for the requirement to be met, as groups of alternatives of which one has to
+/// be found each. A requirement that can be met without any executable results in no groups.
+fn required_executables(requirement: &Requirement) -> Vec<Vec<ExecutableRequirement>> {
+    match requirement {
+        Requirement::Executable(executable) => vec![vec![executable.clone()]],
+        Requirement::All { requirements, .. } => {
+            requirements.iter().flat_map(required_executables).collect()
+        }
+        Requirement::Any { requirements, .. } => {
+            // Any of the requirements having all of its groups met is enough, so each combination of one group per
+            // requirement forms a group of alternatives
+            let mut groups: Vec<Vec<ExecutableRequirement>> = vec![Vec::new()];
+            for branch_groups in requirements.iter().map(required_executables) {
+                if branch_groups.is_empty() {
+                    return Vec::new();
+                }
+                groups = groups
+                    .iter()
+                    .flat_map(|group| {
+                        branch_groups.iter().map(move |branch_group| {
+                            let mut group = group.clone();
+                            for requirement in branch_group {
+                                if !group.contains(requirement) {
+                                    group.push(requirement.clone());
+                                }
+                            }
+                            group
+                        })
+                    })
+                    .collect();
+            }
+            groups.retain(|group| !group.is_empty());
+            groups
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn task_requirements(steps: &[TaskStep]) -> impl Iterator<Item = &Requirement> {
+    steps.iter().filter_map(|step| match step {
+        TaskStep::Wait { requirement, .. } => Some(requirement),
+        _ => None,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct PreflightCheck {
+    /// Executables of which one has to be found, usually only one unless they're alternatives of an `any` requirement
+    pub alternatives: Vec<ExecutableRequirement>,
+    pub workdir: String,
+    /// Blocks and tasks having the requirement
+    pub required_by: Vec<String>,
+    pub status: PreflightStatus,
+}
+
+#[derive(Debug, Clone)]
+pub enum PreflightStatus {
+    Pending,
+    Running,
+    Ok(String),
+    Failed(Vec<String>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::required_executables;
+    use crate::config::Requirement;
+
+    fn executables(yaml: &str) -> Vec<Vec<String>> {
+        let requirement: Requirement = serde_yaml::from_str(yaml).unwrap();
+        required_executables(&requirement)
+            .into_iter()
+            .map(|group| {
+                group
+                    .into_iter()
+                    .map(|requirement| requirement.executable)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_required_executables() {
+        assert_eq!(
+            executables(
+                "
+type: all
+requirements:
+  - type: executable
+    executable: java
+  - type: any
+    requirements:
+      - type: executable
+        executable: mvn
+      - type: executable
+        executable: ./mvnw
+"
+            ),
+            vec![vec!["java"], vec!["mvn", "./mvnw"]]
+        );
+
+        // Either all executables of the first branch or the one of the second branch have to be found
+        assert_eq!(
+            executables(
+                "
+type: any
+requirements:
+  - type: all
+    requirements:
+      - type: executable
+        executable: node
+      - type: executable
+        executable: npm
+  - type: executable
+    executable: bun
+"
+            ),
+            vec![vec!["node", "bun"], vec!["npm", "bun"]]
+        );
+
+        // Nothing has to be found if a branch can be met without executables, or if the requirement is negated
+        assert!(
+            executables(
+                "
+type: any
+requirements:
+  - type: executable
+    executable: docker
+  - type: port
+    port: 5432
+"
+            )
+            .is_empty()
+        );
+        assert!(
+            executables(
+                "
+type: not
+requirement:
+  type: executable
+  executable: legacy-tool
+"
+            )
+            .is_empty()
+        );
+    }
+}
@@ -0,0 +1,482 @@
+use crate::config::{BlockId, Config, ProblemMatcher, ProblemSeverity, ServiceId};
+use crate::models::{OutputKey, OutputKind};
+use log::error;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+/// Number of lines after a message within which its location has to follow
+const MAX_LOCATION_DISTANCE: usize = 3;
+
+/// A diagnostic extracted from the output of a block.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub block_id: BlockId,
+    pub severity: ProblemSeverity,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// Index of the output line the problem was extracted from
+    pub output_index: u128,
+}
+impl Problem {
+    /// Location in the form `file:line:column`, omitting the parts that aren't known.
+    pub fn location(&self) -> Option<String> {
+        let file = self.file.as_ref()?;
+        Some(match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{file}:{line}:{column}"),
+            (Some(line), None) => format!("{file}:{line}"),
+            _ => file.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompiledProblemMatcher {
+    pattern: Regex,
+    location: Option<Regex>,
+    exclude: Option<Regex>,
+    severity: Option<ProblemSeverity>,
+}
+impl CompiledProblemMatcher {
+    fn compile(matcher: &ProblemMatcher) -> Option<Self> {
+        // Patterns are validated when loading the config, so this shouldn't happen
+        let compile = |pattern: &str| {
+            Regex::new(pattern)
+                .inspect_err(|error| error!("Invalid problem matcher pattern '{pattern}': {error}"))
+                .ok()
+        };
+        Some(CompiledProblemMatcher {
+            pattern: compile(&matcher.pattern)?,
+            location: match &matcher.location {
+                Some(location) => Some(compile(location)?),
+                None => None,
+            },
+            exclude: match &matcher.exclude {
+                Some(exclude) => Some(compile(exclude)?),
+                None => None,
+            },
+            severity: matcher.severity,
+        })
+    }
+}
+
+/// Location of a problem, as found on a line of output.
+#[derive(Debug, Clone, Default)]
+struct Location {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+}
+impl Location {
+    fn from_captures(captures: &Captures) -> Self {
+        Self {
+            file: captures.name("file").map(|file| file.as_str().to_string()),
+            line: captures
+                .name("line")
+                .and_then(|line| line.as_str().parse().ok()),
+            column: captures
+                .name("column")
+                .and_then(|column| column.as_str().parse().ok()),
+        }
+    }
+
+    fn apply(self, problem: &mut Problem) {
+        problem.file = self.file;
+        problem.line = self.line;
+        problem.column = self.column;
+    }
+}
+
+/// Result of matching a line against the problem matchers of its block. Since this doesn't depend on the preceding
+/// lines, it's determined before the line is added to the system state.
+#[derive(Debug, Clone, Default)]
+pub struct ProblemLineMatch {
+    /// The first matcher whose pattern matches the line, along with the problem it describes
+    problem: Option<(usize, Problem)>,
+    /// Location found on the line by each of the matchers, for the matchers with a location pattern
+    locations: Vec<Option<Location>>,
+}
+
+/// Compiled versions of the problem matchers defined for blocks.
+#[derive(Debug, Clone, Default)]
+pub struct ProblemMatchers {
+    by_block: HashMap<(ServiceId, BlockId), Vec<CompiledProblemMatcher>>,
+}
+impl ProblemMatchers {
+    pub fn new(config: &Config) -> Self {
+        let by_block = config
+            .services
+            .iter()
+            .flat_map(|service| {
+                service.blocks.iter().map(|block| {
+                    let matchers = block
+                        .problem_matchers
+                        .iter()
+                        .filter_map(|matcher| CompiledProblemMatcher::compile(&matcher.resolve()))
+                        .collect::<Vec<_>>();
+                    ((service.id.clone(), block.id.clone()), matchers)
+                })
+            })
+            .filter(|(_, matchers)| !matchers.is_empty())
+            .collect();
+
+        Self { by_block }
+    }
+
+    /// Matches a line of output of a block against the patterns and location patterns of its matchers.
+    pub fn match_line(
+        &self,
+        service_id: &ServiceId,
+        block_id: &BlockId,
+        line: &str,
+    ) -> ProblemLineMatch {
+        let Some(matchers) = self.by_block.get(&(service_id.clone(), block_id.clone())) else {
+            return ProblemLineMatch::default();
+        };
+
+        let problem = matchers.iter().enumerate().find_map(|(index, matcher)| {
+            let captures = matcher.pattern.captures(line)?;
+            if matcher
+                .exclude
+                .as_ref()
+                .is_some_and(|exclude| exclude.is_match(line))
+            {
+                return None;
+            }
+
+            let mut problem = Problem {
+                block_id: block_id.clone(),
+                severity: captures
+                    .name("severity")
+                    .and_then(|severity| ProblemSeverity::parse(severity.as_str()))
+                    .or(matcher.severity)
+                    .unwrap_or(ProblemSeverity::Error),
+                message: captures
+                    .name("message")
+                    .map(|message| message.as_str().trim())
+                    .unwrap_or(line)
+                    .to_string(),
+                file: None,
+                line: None,
+                column: None,
+                output_index: 0,
+            };
+            if matcher.location.is_none() {
+                Location::from_captures(&captures).apply(&mut problem);
+            }
+            Some((index, problem))
+        });
+
+        ProblemLineMatch {
+            problem,
+            locations: matchers
+                .iter()
+                .map(|matcher| {
+                    let captures = matcher.location.as_ref()?.captures(line)?;
+                    Some(Location::from_captures(&captures))
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether the problem of the given matcher is only complete once its location follows on a separate line.
+    fn has_location(&self, service_id: &ServiceId, block_id: &BlockId, matcher: usize) -> bool {
+        self.by_block
+            .get(&(service_id.clone(), block_id.clone()))
+            .and_then(|matchers| matchers.get(matcher))
+            .is_some_and(|matcher| matcher.location.is_some())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PendingProblem {
+    problem: Problem,
+    matcher: usize,
+    lines_left: usize,
+}
+
+/// Problems of each output that are still waiting for their location.
+#[derive(Debug, Clone, Default)]
+pub struct PendingProblems {
+    pending: HashMap<OutputKey, PendingProblem>,
+}
+impl PendingProblems {
+    /// Discards the problem of a block that is still waiting for its location, as the block is run again.
+    pub fn clear(&mut self, service_id: &ServiceId, block_id: &BlockId) {
+        self.pending.retain(|key, _| {
+            key.kind != OutputKind::ExtProcess
+                || key.service_id.as_ref() != Some(service_id)
+                || key.source_name != block_id.inner()
+        });
+    }
+
+    /// Returns the problem of an output that is still waiting for its location, once no further output follows.
+    pub fn flush(&mut self, key: &OutputKey) -> Option<Problem> {
+        self.pending.remove(key).map(|pending| pending.problem)
+    }
+
+    /// Processes the matches of a line of output of a block, returning the problems that are complete after this line.
+    /// Problems still waiting for their location are returned without one once the location can no longer follow.
+    pub fn process_line(
+        &mut self,
+        matchers: &ProblemMatchers,
+        service_id: &ServiceId,
+        block_id: &BlockId,
+        key: &OutputKey,
+        line_match: ProblemLineMatch,
+        output_index: u128,
+    ) -> Vec<Problem> {
+        let ProblemLineMatch {
+            problem,
+            mut locations,
+        } = line_match;
+
+        // Problems whose location doesn't follow are still reported, just without a location
+        let mut problems = Vec::new();
+        if let Some(mut pending) = self.pending.remove(key) {
+            let location = locations.get_mut(pending.matcher).and_then(Option::take);
+            if let Some(location) = location {
+                location.apply(&mut pending.problem);
+                return vec![pending.problem];
+            } else if pending.lines_left > 1 {
+                pending.lines_left -= 1;
+                self.pending.insert(key.clone(), pending);
+            } else {
+                problems.push(pending.problem);
+            }
+        }
+
+        if let Some((matcher, mut problem)) = problem {
+            problem.output_index = output_index;
+            if matchers.has_location(service_id, block_id, matcher) {
+                let replaced = self.pending.insert(
+                    key.clone(),
+                    PendingProblem {
+                        problem,
+                        matcher,
+                        lines_left: MAX_LOCATION_DISTANCE,
+                    },
+                );
+                problems.extend(replaced.map(|pending| pending.problem));
+            } else {
+                problems.push(problem);
+            }
+        }
+
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompiledProblemMatcher, PendingProblems, Problem, ProblemMatchers};
+    use crate::config::{BlockId, ProblemMatcherPreset, ProblemSeverity, ServiceId};
+    use crate::models::{OutputKey, OutputKind};
+
+    fn matchers(preset: ProblemMatcherPreset) -> ProblemMatchers {
+        let mut matchers = ProblemMatchers::default();
+        matchers.by_block.insert(
+            (ServiceId::new("service"), BlockId::new("build")),
+            vec![CompiledProblemMatcher::compile(&preset.matcher()).unwrap()],
+        );
+        matchers
+    }
+
+    fn key() -> OutputKey {
+        OutputKey {
+            service_id: Some(ServiceId::new("service")),
+            source_name: "build".to_string(),
+            kind: OutputKind::ExtProcess,
+        }
+    }
+
+    /// Feeds the output to the matchers, returning the problems found once it has ended.
+    fn process(
+        matchers: &ProblemMatchers,
+        pending: &mut PendingProblems,
+        output: &str,
+    ) -> Vec<Problem> {
+        let (service_id, block_id) = (ServiceId::new("service"), BlockId::new("build"));
+        let mut problems = output
+            .lines()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                pending.process_line(
+                    matchers,
+                    &service_id,
+                    &block_id,
+                    &key(),
+                    matchers.match_line(&service_id, &block_id, line),
+                    index as u128,
+                )
+            })
+            .collect::<Vec<_>>();
+        problems.extend(pending.flush(&key()));
+        problems
+    }
+
+    fn summary(problems: &[Problem]) -> Vec<(ProblemSeverity, &str, Option<String>)> {
+        problems
+            .iter()
+            .map(|problem| {
+                (
+                    problem.severity,
+                    problem.message.as_str(),
+                    problem.location(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rustc() {
+        let output = r#"   Compiling client v0.1.0 (/home/user/client)
+warning: unused variable: `idx`
+  --> src/flow.rs:22:43
+   |
+22 |     fn measure(&self, ctx: &FrameContext, idx: usize) -> UIResult<Size> {
+   |                                           ^^^ help: if this is intentional, prefix it with an underscore: `_idx`
+   |
+   = note: `#[warn(unused_variables)]` on by default
+
+error[E0308]: mismatched types
+ --> src/main.rs:4:18
+  |
+4 |     let x: i32 = "a";
+  |            ---   ^^^ expected `i32`, found `&str`
+  |            |
+  |            expected due to this
+
+warning: `client` (bin "client") generated 1 warning
+error: could not compile `client` (bin "client") due to 1 previous error; 1 warning emitted
+error: linker `cc` not found"#;
+
+        let problems = process(
+            &matchers(ProblemMatcherPreset::Rustc),
+            &mut PendingProblems::default(),
+            output,
+        );
+        assert_eq!(
+            summary(&problems),
+            vec![
+                (
+                    ProblemSeverity::Warning,
+                    "unused variable: `idx`",
+                    Some("src/flow.rs:22:43".to_string())
+                ),
+                (
+                    ProblemSeverity::Error,
+                    "mismatched types",
+                    Some("src/main.rs:4:18".to_string())
+                ),
+                (ProblemSeverity::Error, "linker `cc` not found", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rustc_rerun() {
+        let (service_id, block_id) = (ServiceId::new("service"), BlockId::new("build"));
+        let matchers = matchers(ProblemMatcherPreset::Rustc);
+        let mut pending = PendingProblems::default();
+        let line_match =
+            matchers.match_line(&service_id, &block_id, "error[E0308]: mismatched types");
+        pending.process_line(&matchers, &service_id, &block_id, &key(), line_match, 0);
+        pending.clear(&service_id, &block_id);
+
+        // The location printed by the next run doesn't belong to the problem of the previous one
+        let problems = process(&matchers, &mut pending, " --> src/main.rs:4:18");
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn test_tsc() {
+        let output = "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+            Found 1 error in src/index.ts:3";
+
+        let problems = process(
+            &matchers(ProblemMatcherPreset::Tsc),
+            &mut PendingProblems::default(),
+            output,
+        );
+        assert_eq!(
+            summary(&problems),
+            vec![(
+                ProblemSeverity::Error,
+                "TS2322: Type 'string' is not assignable to type 'number'.",
+                Some("src/index.ts:3:7".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_javac() {
+        let output = r#"src/main/java/App.java:5: error: cannot find symbol
+        System.out.println(count);
+                           ^
+  symbol:   variable count
+  location: class App
+src/main/java/App.java:9: warning: [removal] finalize() in Object has been deprecated and marked for removal
+1 error
+1 warning"#;
+
+        let problems = process(
+            &matchers(ProblemMatcherPreset::Javac),
+            &mut PendingProblems::default(),
+            output,
+        );
+        assert_eq!(
+            summary(&problems),
+            vec![
+                (
+                    ProblemSeverity::Error,
+                    "cannot find symbol",
+                    Some("src/main/java/App.java:5".to_string())
+                ),
+                (
+                    ProblemSeverity::Warning,
+                    "[removal] finalize() in Object has been deprecated and marked for removal",
+                    Some("src/main/java/App.java:9".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gcc() {
+        let output = r#"main.c: In function 'main':
+main.c:4:5: warning: implicit declaration of function 'foo' [-Wimplicit-function-declaration]
+    4 |     foo();
+      |     ^~~
+main.c:5:12: error: expected ';' before '}' token
+main.c:1:10: fatal error: missing.h: No such file or directory
+compilation terminated."#;
+
+        let problems = process(
+            &matchers(ProblemMatcherPreset::Gcc),
+            &mut PendingProblems::default(),
+            output,
+        );
+        assert_eq!(
+            summary(&problems),
+            vec![
+                (
+                    ProblemSeverity::Warning,
+                    "implicit declaration of function 'foo' [-Wimplicit-function-declaration]",
+                    Some("main.c:4:5".to_string())
+                ),
+                (
+                    ProblemSeverity::Error,
+                    "expected ';' before '}' token",
+                    Some("main.c:5:12".to_string())
+                ),
+                (
+                    ProblemSeverity::Error,
+                    "missing.h: No such file or directory",
+                    Some("main.c:1:10".to_string())
+                ),
+            ]
+        );
+    }
+}
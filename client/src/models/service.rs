@@ -1,19 +1,28 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::time::Instant;
 
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
 use crate::config::{AutomationDefinitionId, Block, BlockId, ServiceDefinition};
-use crate::models::Automation;
+use crate::models::{Automation, Problem};
 
 #[derive(Debug, Clone)]
 pub struct Service {
     pub definition: ServiceDefinition,
     block_statuses: HashMap<BlockId, BlockStatus>,
     block_actions: HashMap<BlockId, BlockAction>,
+    /// Times at which each block has started working, oldest first
+    block_start_times: HashMap<BlockId, VecDeque<DateTime<Local>>>,
     pub automations: Vec<Automation>,
     pub output_enabled: bool,
     pub automation_enabled: bool,
+    /// Number of error-level output lines received since the output of the service was last viewed
+    pub unseen_errors: usize,
+    /// Problems extracted from the output of the blocks of the service, cleared for a block when it runs again
+    pub problems: Vec<Problem>,
+    /// Latest failed requirement check of each block, cleared for a block when it runs again
+    check_failures: HashMap<BlockId, CheckFailure>,
 }
 impl Service {
     pub fn update_block_status(&mut self, block_id: &BlockId, status: BlockStatus) {
@@ -26,9 +35,50 @@ impl Service {
             return;
         }
 
+        let was_working = matches!(
+            self.block_statuses.get(block_id),
+            Some(BlockStatus::Working { .. })
+        );
+        if !was_working && matches!(status, BlockStatus::Working { .. }) {
+            let start_times = self.block_start_times.entry(block_id.clone()).or_default();
+            start_times.push_back(Local::now());
+            if start_times.len() > 64 {
+                start_times.pop_front();
+            }
+            self.problems
+                .retain(|problem| &problem.block_id != block_id);
+            self.check_failures.remove(block_id);
+        }
+
         self.block_statuses.insert(block_id.clone(), status);
     }
 
+    /// Returns the start time of the latest run of the block that started at or before the given time.
+    pub fn get_block_start_time(
+        &self,
+        block_id: &BlockId,
+        at: &DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        self.block_start_times
+            .get(block_id)
+            .and_then(|start_times| start_times.iter().rev().find(|time| *time <= at))
+            .cloned()
+    }
+
+    pub fn record_check_failure(&mut self, block_id: &BlockId, message: String) {
+        self.check_failures.insert(
+            block_id.clone(),
+            CheckFailure {
+                message,
+                time: Instant::now(),
+            },
+        );
+    }
+
+    pub fn get_check_failure(&self, block_id: &BlockId) -> Option<&CheckFailure> {
+        self.check_failures.get(block_id)
+    }
+
     pub fn get_block_status(&self, block_id: &BlockId) -> BlockStatus {
         self.block_statuses
             .get(block_id)
@@ -73,6 +123,7 @@ impl From<ServiceDefinition> for Service {
     fn from(value: ServiceDefinition) -> Self {
         Service {
             block_statuses: HashMap::new(),
+            block_start_times: HashMap::new(),
             block_actions: value
                 .blocks
                 .iter()
@@ -86,10 +137,20 @@ impl From<ServiceDefinition> for Service {
             definition: value,
             output_enabled: true,
             automation_enabled: true,
+            unseen_errors: 0,
+            problems: Vec::new(),
+            check_failures: HashMap::new(),
         }
     }
 }
 
+/// Reason why a requirement check of a block failed, kept to be displayed while the check is retried.
+#[derive(Debug, Clone)]
+pub struct CheckFailure {
+    pub message: String,
+    pub time: Instant,
+}
+
 #[derive(Debug, Clone)]
 pub enum BlockStatus {
     Initial,
@@ -100,11 +161,43 @@ pub enum BlockStatus {
         // If `false`, then the block bypassed its work-steps due to a pre-work health check succeeding. This is only
         // possible for some types of blocks. `true` otherwise.
         was_worked: bool,
+        liveness: Liveness,
     },
     Error,
     Disabled,
 }
 
+/// Progress of the liveness checks of a healthy block. Only advanced if liveness checks are configured for the block.
+#[derive(Debug, Clone)]
+pub struct Liveness {
+    /// Start of the current round of checks, or the end of the previous round while waiting for the next one
+    pub round_time: Instant,
+    /// Whether a round of checks is currently in progress
+    pub checking: bool,
+    pub checks_completed: usize,
+    /// Number of rounds that have failed in a row
+    pub consecutive_failures: usize,
+    /// Set once the number of consecutive failures reaches the failure threshold
+    pub unhealthy: bool,
+}
+impl Liveness {
+    /// Whether the latest round of checks failed without the block being considered unhealthy yet
+    pub fn is_degraded(&self) -> bool {
+        self.consecutive_failures > 0 && !self.unhealthy
+    }
+}
+impl Default for Liveness {
+    fn default() -> Self {
+        Self {
+            round_time: Instant::now(),
+            checking: false,
+            checks_completed: 0,
+            consecutive_failures: 0,
+            unhealthy: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum WorkStep {
     ResourceGroupCheck {
@@ -132,6 +225,9 @@ pub enum WorkStep {
     PerformWork {
         current_step_started: Instant,
         steps_completed: usize,
+        /// Time at which the latest attempt of the current step failed, if it is to be retried
+        last_failure: Option<Instant>,
+        failed_attempts: usize,
         /// The fingerprint calculated before performing work, to be stored after success.
         new_fingerprint: Option<String>,
     },
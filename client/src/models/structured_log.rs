@@ -0,0 +1,291 @@
+use crate::config::LogLevel;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+
+const LEVEL_FIELDS: [&str; 4] = ["level", "lvl", "severity", "log.level"];
+const TIME_FIELDS: [&str; 4] = ["time", "timestamp", "ts", "@timestamp"];
+const MESSAGE_FIELDS: [&str; 3] = ["msg", "message", "event"];
+
+/// A line of output that was parsed as a JSON object.
+#[derive(Debug, Clone)]
+pub struct StructuredLine {
+    pub fields: Map<String, Value>,
+}
+impl StructuredLine {
+    /// Parses a line as a JSON object. Returns `None` for any other kind of line, including other JSON values.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if !line.starts_with('{') {
+            return None;
+        }
+        match serde_json::from_str(line) {
+            Ok(Value::Object(fields)) => Some(Self { fields }),
+            _ => None,
+        }
+    }
+
+    /// Looks up a field by name. Names containing dots are also looked up as paths into nested objects.
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.fields.get(name).or_else(|| {
+            let mut parts = name.split('.');
+            let first = self.fields.get(parts.next()?)?;
+            parts.try_fold(first, |value, part| value.as_object()?.get(part))
+        })
+    }
+
+    pub fn level(&self) -> Option<LogLevel> {
+        LEVEL_FIELDS
+            .iter()
+            .find_map(|name| self.field(name))
+            .and_then(parse_level_value)
+    }
+
+    /// Compact single line representation in the form `LEVEL time message key=value ...`.
+    pub fn summary(&self) -> String {
+        let find = |names: &[&'static str]| {
+            names
+                .iter()
+                .find_map(|name| self.fields.get(*name).map(|value| (*name, value)))
+        };
+        let level = find(&LEVEL_FIELDS);
+        let time = find(&TIME_FIELDS);
+        let message = find(&MESSAGE_FIELDS);
+
+        let mut parts = Vec::new();
+        if let Some((_, level)) = level {
+            parts.push(format!("{:<5}", display_value(level).to_uppercase()));
+        }
+        if let Some((_, time)) = time {
+            parts.push(display_value(time));
+        }
+        if let Some((_, message)) = message {
+            parts.push(display_value(message));
+        }
+
+        let used: Vec<&str> = [level, time, message]
+            .iter()
+            .flatten()
+            .map(|(name, _)| *name)
+            .collect();
+        for (name, value) in &self.fields {
+            if !used.contains(&name.as_str()) {
+                let value = display_value(value);
+                if value.contains(char::is_whitespace) {
+                    parts.push(format!("{name}={value:?}"));
+                } else {
+                    parts.push(format!("{name}={value}"));
+                }
+            }
+        }
+
+        parts.join(" ")
+    }
+
+    pub fn pretty(&self) -> String {
+        serde_json::to_string_pretty(&self.fields).unwrap_or_default()
+    }
+}
+
+/// Parses a log level from its name, or from a numeric level as used by pino and bunyan.
+pub fn parse_level_value(value: &Value) -> Option<LogLevel> {
+    match value {
+        Value::String(name) => parse_level_name(name),
+        Value::Number(number) => number.as_f64().map(|number| match number {
+            ..=20.0 => LogLevel::Debug,
+            ..=30.0 => LogLevel::Info,
+            ..=40.0 => LogLevel::Warn,
+            _ => LogLevel::Error,
+        }),
+        _ => None,
+    }
+}
+
+pub fn parse_level_name(name: &str) -> Option<LogLevel> {
+    match name.to_lowercase().as_str() {
+        "trace" | "debug" | "verbose" => Some(LogLevel::Debug),
+        "info" | "information" | "notice" => Some(LogLevel::Info),
+        "warn" | "warning" => Some(LogLevel::Warn),
+        "error" | "err" | "fatal" | "critical" | "crit" | "panic" => Some(LogLevel::Error),
+        _ => None,
+    }
+}
+
+/// Formats a value for display, without quoting strings.
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Comparison {
+    Eq,
+    NotEq,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
+}
+impl Comparison {
+    fn matches(&self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Eq => ordering.is_eq(),
+            Comparison::NotEq => ordering.is_ne(),
+            Comparison::Less => ordering.is_lt(),
+            Comparison::LessEq => ordering.is_le(),
+            Comparison::Greater => ordering.is_gt(),
+            Comparison::GreaterEq => ordering.is_ge(),
+        }
+    }
+}
+
+/// Condition on a field of structured lines, such as `traceId=abc` or `duration>100`. The `level` field is compared by
+/// severity, and also applies to lines that aren't structured, based on the level assigned by the highlight rules.
+#[derive(Debug, Clone)]
+pub struct FieldCondition {
+    field: String,
+    comparison: Comparison,
+    value: String,
+}
+impl FieldCondition {
+    /// Parses a filter term of the form `<field><op><value>`, where `op` is one of `=`, `!=`, `<`, `<=`, `>` or `>=`.
+    pub fn parse(term: &str) -> Option<Self> {
+        let start = term.find(['=', '!', '<', '>'])?;
+        let (field, rest) = term.split_at(start);
+        let (comparison, value) = [
+            ("!=", Comparison::NotEq),
+            ("<=", Comparison::LessEq),
+            (">=", Comparison::GreaterEq),
+            ("=", Comparison::Eq),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+        ]
+        .into_iter()
+        .find_map(|(op, comparison)| rest.strip_prefix(op).map(|value| (comparison, value)))?;
+
+        if field.is_empty()
+            || !field
+                .chars()
+                .all(|char| char.is_alphanumeric() || "_-.@".contains(char))
+        {
+            return None;
+        }
+
+        Some(Self {
+            field: field.to_owned(),
+            comparison,
+            value: value.to_owned(),
+        })
+    }
+
+    pub fn is_level_condition(&self) -> bool {
+        LEVEL_FIELDS.contains(&self.field.as_str())
+    }
+
+    /// Whether the value of a level condition is a known level. Conditions on other fields are always valid.
+    pub fn is_valid(&self) -> bool {
+        !self.is_level_condition() || parse_level_name(&self.value).is_some()
+    }
+
+    /// Checks the condition against the level of a line.
+    pub fn matches_level(&self, level: Option<LogLevel>) -> bool {
+        match (level, parse_level_name(&self.value)) {
+            (Some(level), Some(expected)) => self.comparison.matches(level.cmp(&expected)),
+            _ => false,
+        }
+    }
+
+    /// Checks the condition against a field of a structured line. Values are compared as numbers if both sides are
+    /// numeric, and as text otherwise.
+    pub fn matches(&self, line: &StructuredLine) -> bool {
+        let Some(value) = line.field(&self.field) else {
+            return self.comparison == Comparison::NotEq;
+        };
+        let value = display_value(value);
+
+        let ordering = match (value.parse::<f64>(), self.value.parse::<f64>()) {
+            (Ok(actual), Ok(expected)) => actual.partial_cmp(&expected),
+            _ => Some(value.as_str().cmp(self.value.as_str())),
+        };
+        ordering
+            .map(|ordering| self.comparison.matches(ordering))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldCondition, StructuredLine};
+    use crate::config::LogLevel;
+
+    #[test]
+    fn test_parse() {
+        assert!(StructuredLine::parse(r#"  {"msg":"started"}  "#).is_some());
+        assert!(StructuredLine::parse(r#"[{"msg":"started"}]"#).is_none());
+        assert!(StructuredLine::parse(r#"{"msg":"#).is_none());
+        assert!(StructuredLine::parse("started").is_none());
+    }
+
+    #[test]
+    fn test_fields() {
+        let line = StructuredLine::parse(
+            r#"{"log":{"level":"warn"},"http.status":503,"level":30,"msg":"slow request"}"#,
+        )
+        .unwrap();
+        assert_eq!(line.field("http.status").unwrap(), 503);
+        assert_eq!(line.field("log.level").unwrap(), "warn");
+        assert!(line.field("log.missing").is_none());
+
+        // Numeric levels as used by pino
+        assert_eq!(line.level(), Some(LogLevel::Info));
+        let line = StructuredLine::parse(r#"{"severity":"CRITICAL"}"#).unwrap();
+        assert_eq!(line.level(), Some(LogLevel::Error));
+    }
+
+    #[test]
+    fn test_summary() {
+        let line = StructuredLine::parse(
+            r#"{"level":"info","time":"12:00:01","msg":"request done","path":"/api/users","user agent":"curl 8.0","ms":12}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            line.summary(),
+            r#"INFO  12:00:01 request done ms=12 path=/api/users user agent="curl 8.0""#
+        );
+    }
+
+    #[test]
+    fn test_field_condition() {
+        let line =
+            StructuredLine::parse(r#"{"traceId":"abc","status":"503","duration":95.5}"#).unwrap();
+        let matches = |term: &str| FieldCondition::parse(term).unwrap().matches(&line);
+
+        assert!(matches("traceId=abc"));
+        assert!(!matches("traceId!=abc"));
+        // Compared as numbers if both sides are numeric, so "95.5" isn't less than "100" as text
+        assert!(matches("duration<100"));
+        assert!(matches("status>=500"));
+        assert!(!matches("status>503"));
+        // Missing fields only satisfy `!=`
+        assert!(!matches("user=bob"));
+        assert!(matches("user!=bob"));
+
+        assert!(FieldCondition::parse("error").is_none());
+        assert!(FieldCondition::parse("=abc").is_none());
+        assert!(FieldCondition::parse("a b=c").is_none());
+    }
+
+    #[test]
+    fn test_level_condition() {
+        let condition = FieldCondition::parse("level>=warn").unwrap();
+        assert!(condition.is_level_condition());
+        assert!(condition.is_valid());
+        assert!(condition.matches_level(Some(LogLevel::Error)));
+        assert!(!condition.matches_level(Some(LogLevel::Info)));
+        assert!(!condition.matches_level(None));
+
+        assert!(!FieldCondition::parse("level=loud").unwrap().is_valid());
+        assert!(FieldCondition::parse("status=loud").unwrap().is_valid());
+    }
+}
@@ -21,6 +21,8 @@ pub enum TaskStatus {
     Running {
         step_start_time: Instant,
         last_recoverable_failure: Option<Instant>,
+        /// Number of failed attempts of the current step
+        failed_attempts: usize,
         completed_steps: usize,
     },
     Finished,
@@ -32,6 +34,7 @@ impl Default for TaskStatus {
             completed_steps: 0,
             step_start_time: Instant::now(),
             last_recoverable_failure: None,
+            failed_attempts: 0,
         }
     }
 }
@@ -0,0 +1,241 @@
+use crate::config::{LogFileConfig, ServiceId};
+use crate::models::OutputKind;
+use crate::system_state::SystemState;
+use chrono::{DateTime, Local};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use log::{error, info};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// A single line of output to be persisted by a log sink.
+pub struct LogSinkLine {
+    pub service_id: ServiceId,
+    pub source_name: String,
+    pub kind: OutputKind,
+    pub timestamp: DateTime<Local>,
+    pub value: String,
+}
+
+/// Appends the output of services with a configured log file to rotating files. Lines are received over a channel,
+/// so that file operations never block the threads producing output.
+pub struct LogSinkWriter {
+    keep_alive: Arc<Mutex<bool>>,
+    state: Arc<RwLock<SystemState>>,
+}
+impl LogSinkWriter {
+    pub fn new(state: Arc<RwLock<SystemState>>) -> Self {
+        Self {
+            keep_alive: Arc::new(Mutex::new(true)),
+            state,
+        }
+    }
+
+    pub fn start(&self) -> JoinHandle<()> {
+        let keep_alive = self.keep_alive.clone();
+        let (tx, rx) = channel::<LogSinkLine>();
+
+        let mut sinks: HashMap<ServiceId, LogSink> = {
+            let mut state = self.state.write().unwrap();
+            let data_dir = PathBuf::from(&state.resolved_data_dir);
+            let sinks = state
+                .config
+                .services
+                .iter()
+                .filter_map(|service| {
+                    service.output.log_file.as_ref().map(|config| {
+                        let path = match &config.path {
+                            Some(path) => data_dir.join(path),
+                            None => data_dir
+                                .join("logs")
+                                .join(format!("{}.log", service.id.inner())),
+                        };
+                        (service.id.clone(), LogSink::new(config.clone(), path))
+                    })
+                })
+                .collect::<HashMap<_, _>>();
+
+            if !sinks.is_empty() {
+                state.log_sink_tx = Some(tx);
+            }
+            sinks
+        };
+
+        thread::spawn(move || {
+            while *keep_alive.lock().unwrap() {
+                match rx.recv_timeout(Duration::from_millis(100)) {
+                    Ok(line) => {
+                        Self::write_line(&mut sinks, line);
+                        // Write everything that's queued up before flushing
+                        while let Ok(line) = rx.try_recv() {
+                            Self::write_line(&mut sinks, line);
+                        }
+                        sinks.values_mut().for_each(LogSink::flush);
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+
+            Self::drain(&mut sinks, &rx);
+        })
+    }
+
+    pub fn stop(&self) {
+        *self.keep_alive.lock().unwrap() = false;
+    }
+
+    fn drain(sinks: &mut HashMap<ServiceId, LogSink>, rx: &Receiver<LogSinkLine>) {
+        while let Ok(line) = rx.try_recv() {
+            Self::write_line(sinks, line);
+        }
+        sinks.values_mut().for_each(LogSink::flush);
+    }
+
+    fn write_line(sinks: &mut HashMap<ServiceId, LogSink>, line: LogSinkLine) {
+        if let Some(sink) = sinks.get_mut(&line.service_id)
+            && sink.accepts(&line.source_name)
+            && let Err(err) = sink.write(&line)
+        {
+            error!(
+                "Failed to write to log file {path}: {err}",
+                path = sink.path.display()
+            );
+        }
+    }
+}
+
+struct LogSink {
+    config: LogFileConfig,
+    path: PathBuf,
+    file: Option<BufWriter<File>>,
+    size: u64,
+}
+impl LogSink {
+    fn new(config: LogFileConfig, path: PathBuf) -> Self {
+        Self {
+            config,
+            path,
+            file: None,
+            size: 0,
+        }
+    }
+
+    fn accepts(&self, source_name: &str) -> bool {
+        self.config
+            .sources
+            .as_ref()
+            .map(|sources| sources.iter().any(|source| source == source_name))
+            .unwrap_or(true)
+    }
+
+    fn write(&mut self, line: &LogSinkLine) -> std::io::Result<()> {
+        let formatted = format!(
+            "{timestamp} {kind}/{source} | {value}\n",
+            timestamp = line.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
+            kind = match line.kind {
+                OutputKind::System => "i",
+                OutputKind::ExtProcess => "c",
+            },
+            source = line.source_name,
+            value = line.value,
+        );
+
+        if self.file.is_none() {
+            self.open()?;
+        }
+        if self.size > 0 && self.size + formatted.len() as u64 > self.config.max_size {
+            self.rotate()?;
+            self.open()?;
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(formatted.as_bytes())?;
+            self.size += formatted.len() as u64;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) {
+        if let Some(file) = self.file.as_mut()
+            && let Err(err) = file.flush()
+        {
+            error!(
+                "Failed to flush log file {path}: {err}",
+                path = self.path.display()
+            );
+        }
+    }
+
+    fn open(&mut self) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = file.metadata()?.len();
+        self.file = Some(BufWriter::new(file));
+
+        Ok(())
+    }
+
+    /// Rotates the current file to `<path>.1`, shifting existing rotated files up by one and deleting the oldest ones
+    /// beyond the configured number of files.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+
+        let max_files = self.config.max_files;
+        if max_files == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+
+        let oldest = self.rotated_path(max_files);
+        if oldest.exists() {
+            std::fs::remove_file(&oldest)?;
+        }
+        for index in (1..max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                std::fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+
+        if self.config.compress {
+            compress_file(&self.path, &self.rotated_path(1))?;
+            std::fs::remove_file(&self.path)?;
+        } else {
+            std::fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        info!("Rotated log file {path}", path = self.path.display());
+
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        if self.config.compress {
+            path.push(".gz");
+        }
+        PathBuf::from(path)
+    }
+}
+
+fn compress_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    let mut input = File::open(from)?;
+    let mut encoder = GzEncoder::new(BufWriter::new(File::create(to)?), Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.flush()
+}
@@ -2,6 +2,8 @@ extern crate core;
 
 pub mod file_watcher;
 pub mod fingerprint_checker;
+pub mod log_sink_writer;
+pub mod output_collector;
 pub mod scripting;
 pub mod service_worker;
 
@@ -0,0 +1,141 @@
+use crate::models::{ClassifiedLine, OutputClassifier, OutputKey};
+use crate::system_state::SystemState;
+use chrono::{DateTime, Local};
+use std::sync::mpsc::{RecvTimeoutError, Sender, channel};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Maximum number of lines added while holding the lock on the system state, so that rendering isn't stalled by a
+/// large backlog of output
+const MAX_BATCH_SIZE: usize = 1000;
+
+/// Output received from a process, waiting to be added to the output store.
+pub enum ReceivedOutput {
+    Line {
+        key: OutputKey,
+        line: String,
+        timestamp: DateTime<Local>,
+        classified: Box<ClassifiedLine>,
+    },
+    /// The process closed its output streams, so no further lines follow for the key
+    End { key: OutputKey },
+}
+impl ReceivedOutput {
+    fn apply(self, state: &mut SystemState) {
+        match self {
+            ReceivedOutput::Line {
+                key,
+                line,
+                timestamp,
+                classified,
+            } => state.add_classified_output(&key, line, timestamp, *classified),
+            ReceivedOutput::End { key } => state.end_output(&key),
+        }
+    }
+}
+
+/// Adds the output of processes to the system state in batches. Threads reading the output of processes send their
+/// lines over a channel instead of locking the system state for every line.
+pub struct OutputCollector {
+    keep_alive: Arc<Mutex<bool>>,
+    state: Arc<RwLock<SystemState>>,
+}
+impl OutputCollector {
+    pub fn new(state: Arc<RwLock<SystemState>>) -> Self {
+        Self {
+            keep_alive: Arc::new(Mutex::new(true)),
+            state,
+        }
+    }
+
+    pub fn start(&self) -> JoinHandle<()> {
+        let keep_alive = self.keep_alive.clone();
+        let state = self.state.clone();
+        let (tx, rx) = channel::<ReceivedOutput>();
+        self.state.write().unwrap().output_tx = Some(tx);
+
+        thread::spawn(move || {
+            while *keep_alive.lock().unwrap() {
+                match rx.recv_timeout(Duration::from_millis(100)) {
+                    Ok(output) => {
+                        // Collect whatever else is already queued up, so that it's added under a single lock
+                        let mut batch = vec![output];
+                        while batch.len() < MAX_BATCH_SIZE
+                            && let Ok(output) = rx.try_recv()
+                        {
+                            batch.push(output);
+                        }
+
+                        let mut state = state.write().unwrap();
+                        for output in batch {
+                            output.apply(&mut state);
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+
+            // Don't lose the output that is still queued up
+            let mut state = state.write().unwrap();
+            state.output_tx = None;
+            while let Ok(output) = rx.try_recv() {
+                output.apply(&mut state);
+            }
+        })
+    }
+
+    pub fn stop(&self) {
+        *self.keep_alive.lock().unwrap() = false;
+    }
+}
+
+/// Adds a line of output through the collector, or directly to the system state if the collector isn't running. The
+/// line is classified by the calling thread, before the system state is locked.
+pub fn submit_output(
+    state: &Arc<RwLock<SystemState>>,
+    output_tx: Option<&Sender<ReceivedOutput>>,
+    classifier: &OutputClassifier,
+    key: &OutputKey,
+    line: String,
+) {
+    let timestamp = Local::now();
+    let classified = Box::new(classifier.classify(key, &line));
+    submit(
+        state,
+        output_tx,
+        ReceivedOutput::Line {
+            key: key.clone(),
+            line,
+            timestamp,
+            classified,
+        },
+    );
+}
+
+/// Marks the end of an output through the collector, so that it's handled after the lines that are still queued up.
+pub fn submit_output_end(
+    state: &Arc<RwLock<SystemState>>,
+    output_tx: Option<&Sender<ReceivedOutput>>,
+    key: &OutputKey,
+) {
+    submit(state, output_tx, ReceivedOutput::End { key: key.clone() });
+}
+
+fn submit(
+    state: &Arc<RwLock<SystemState>>,
+    output_tx: Option<&Sender<ReceivedOutput>>,
+    output: ReceivedOutput,
+) {
+    match output_tx {
+        Some(tx) => {
+            if let Err(err) = tx.send(output) {
+                // The collector has already stopped, so apply the output directly
+                err.0.apply(&mut state.write().unwrap());
+            }
+        }
+        None => output.apply(&mut state.write().unwrap()),
+    }
+}
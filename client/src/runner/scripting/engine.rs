@@ -1,10 +1,12 @@
-use crate::config::{BlockId, ServiceId, TaskDefinitionId};
-use crate::models::{BlockAction, BlockStatus, WorkStep};
+use crate::config::{BlockId, ExportFormat, OutputExportSettings, ServiceId, TaskDefinitionId};
+use crate::models::{
+    BlockAction, BlockStatus, OutputExport, OutputKey, WorkStep, resolve_export_path,
+};
 use crate::system_state::SystemState;
 use rhai::module_resolvers::DummyModuleResolver;
 use rhai::packages::{Package, StandardPackage};
 use rhai::plugin::RhaiResult;
-use rhai::{Dynamic, Engine, Map, Scope};
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope};
 use std::sync::{Arc, RwLock};
 
 pub struct ScriptEngine {
@@ -131,6 +133,20 @@ impl ScriptEngine {
                 });
             });
         }
+        {
+            let state_arc = state_arc.clone();
+            function_engine.register_fn("export_output", move |options: Map| {
+                let export = collect_export(&state_arc.read().unwrap(), options)?;
+                write_export(export)
+            });
+        }
+        {
+            let state_arc = state_arc.clone();
+            function_engine.register_fn("export_output", move || {
+                let export = collect_export(&state_arc.read().unwrap(), Map::new())?;
+                write_export(export)
+            });
+        }
     }
 
     fn register_proxies(state: Arc<RwLock<SystemState>>, engine: &mut Engine) {
@@ -192,7 +208,7 @@ impl ScriptEngine {
                 let state = state.read().unwrap();
                 if let Some(service) = state.get_service(&ServiceId::new(&blk.service_id)) {
                     match service.get_block_status(&BlockId::new(&blk.block_id)) {
-                        BlockStatus::Ok { was_worked } => !was_worked,
+                        BlockStatus::Ok { was_worked, .. } => !was_worked,
                         _ => false,
                     }
                 } else {
@@ -234,6 +250,80 @@ impl ScriptEngine {
     }
 }
 
+/// Collects output to export to a file. Supported options are `services` and `sources` (arrays of names to include,
+/// defaulting to all), `lines`, `format` (`"text"` or `"jsonl"`), `timestamps` and `path`. Options that aren't given
+/// default to the export settings.
+fn collect_export(state: &SystemState, options: Map) -> Result<OutputExport, Box<EvalAltResult>> {
+    fn string_list(value: Dynamic) -> Result<Vec<String>, Box<EvalAltResult>> {
+        value
+            .into_array()?
+            .into_iter()
+            .map(|item| item.into_string().map_err(|err| err.into()))
+            .collect()
+    }
+
+    let mut settings: OutputExportSettings = state.config.settings.output.export.clone();
+    let mut services: Option<Vec<String>> = None;
+    let mut sources: Option<Vec<String>> = None;
+    for (name, value) in options {
+        match name.as_str() {
+            "services" => services = Some(string_list(value)?),
+            "sources" => sources = Some(string_list(value)?),
+            "lines" => settings.max_lines = Some(value.as_int()?.max(0) as usize),
+            "timestamps" => settings.timestamps = value.as_bool()?,
+            "path" => settings.path = Some(value.into_string()?),
+            "format" => {
+                settings.format = match value.into_string()?.as_str() {
+                    "text" => ExportFormat::Text,
+                    "jsonl" => ExportFormat::Jsonl,
+                    other => return Err(format!("Unknown export format: {other}").into()),
+                }
+            }
+            other => return Err(format!("Unknown export option: {other}").into()),
+        }
+    }
+
+    let keys: Vec<&OutputKey> = state
+        .output_store
+        .outputs
+        .keys()
+        .filter(|key| {
+            services
+                .as_ref()
+                .map(|services| {
+                    key.service_id
+                        .as_ref()
+                        .is_some_and(|id| services.iter().any(|service| service == id.inner()))
+                })
+                .unwrap_or(true)
+        })
+        .filter(|key| {
+            sources
+                .as_ref()
+                .map(|sources| sources.contains(&key.source_name))
+                .unwrap_or(true)
+        })
+        .collect();
+
+    let path = resolve_export_path(state, &settings);
+    Ok(OutputExport::collect(
+        state,
+        &keys,
+        &settings,
+        path,
+        |_, _| true,
+    ))
+}
+
+/// Writes collected output to its file, returning the path of the file. This is done after releasing the system state,
+/// as the file may be large.
+fn write_export(export: OutputExport) -> Result<String, Box<EvalAltResult>> {
+    export
+        .write()
+        .map(|result| result.path.to_string_lossy().into_owned())
+        .map_err(|err| format!("Failed to export output: {err}").into())
+}
+
 pub struct RhaiRequest {
     pub script: String,
     pub allow_functions: bool,
@@ -2,7 +2,7 @@ use log::{error, info, warn};
 use std::time::{Duration, Instant};
 
 use crate::config::{Fingerprint, WorkDefinition};
-use crate::models::{BlockAction, BlockStatus, WorkStep};
+use crate::models::{BlockAction, BlockStatus, Liveness, WorkStep};
 use crate::runner::fingerprint_checker::FingerprintChecker;
 use crate::runner::service_worker::ConcurrentOperationStatus;
 use crate::runner::service_worker::create_cmd::create_cmd;
@@ -19,6 +19,7 @@ use crate::utils::format_err;
 pub trait BlockProcessor {
     fn process_block(&self);
     fn handle_work(&self);
+    fn check_liveness(&self, was_worked: bool, liveness: Liveness);
 }
 impl BlockProcessor for ServiceBlockContext {
     fn process_block(&self) {
@@ -120,7 +121,13 @@ impl BlockProcessor for ServiceBlockContext {
                 self.clear_current_action();
             }
 
-            (BlockStatus::Ok { .. }, None) => {
+            (
+                BlockStatus::Ok {
+                    was_worked,
+                    liveness,
+                },
+                None,
+            ) => {
                 let require_live_process = self.query_block(|block| match block.work {
                     WorkDefinition::CommandSeq { .. } => false,
                     WorkDefinition::Process { .. } => true,
@@ -128,18 +135,21 @@ impl BlockProcessor for ServiceBlockContext {
 
                 match self.get_concurrent_operation_status(OperationType::Work) {
                     Some(ConcurrentOperationStatus::Running) => {
-                        // Everything is OK
+                        // The process is alive, but it might still have stopped working
+                        self.check_liveness(was_worked, liveness);
                     }
                     _ if require_live_process => {
                         // We don't have a live process and our work is of a type that it requires one. Likely the
                         // process has crashed or has been killed. Enter error-state
+                        self.clear_all_operations();
                         self.add_system_output(
                             "External process has terminated unexpectedly.".to_owned(),
                         );
                         self.update_status(BlockStatus::Error);
                     }
                     _ => {
-                        // We don't have a live process but we don't require one either. Nothing to do.
+                        // We don't have a live process but we don't require one either
+                        self.check_liveness(was_worked, liveness);
                     }
                 }
             }
@@ -237,6 +247,8 @@ impl BlockProcessor for ServiceBlockContext {
                             WorkStep::PerformWork {
                                 current_step_started: Instant::now(),
                                 steps_completed: 0,
+                                last_failure: None,
+                                failed_attempts: 0,
                                 new_fingerprint: None,
                             }
                         },
@@ -294,6 +306,9 @@ impl BlockProcessor for ServiceBlockContext {
                             },
                         });
                     }
+                    RequirementCheckResult::Failed => {
+                        self.update_status(BlockStatus::Error);
+                    }
                     RequirementCheckResult::Timeout => {
                         error!(
                             "Prerequisite check timed out, even though timeout should not be possible"
@@ -310,7 +325,8 @@ impl BlockProcessor for ServiceBlockContext {
                 let result = RequirementChecker {
                     all_requirements: self.query_block(|block| block.health.requirements.clone()),
                     completed_count: checks_completed,
-                    timeout: Some(Duration::from_secs(0)),
+                    // Checks are not retried, so there is no overall timeout to enforce
+                    timeout: None,
                     failure_wait_time: Duration::from_secs(0),
                     start_time,
                     last_failure: None,
@@ -339,12 +355,15 @@ impl BlockProcessor for ServiceBlockContext {
                         });
                     }
                     RequirementCheckResult::CurrentCheckFailed
+                    | RequirementCheckResult::Failed
                     | RequirementCheckResult::Timeout => {
                         // If any check fails, then we must perform the work. Move to the appropriate state
                         self.update_status(BlockStatus::Working {
                             step: WorkStep::PerformWork {
                                 current_step_started: Instant::now(),
                                 steps_completed: 0,
+                                last_failure: None,
+                                failed_attempts: 0,
                                 new_fingerprint: None,
                             },
                         });
@@ -359,7 +378,10 @@ impl BlockProcessor for ServiceBlockContext {
                 match fingerprint_def {
                     None => {
                         // No fingerprint defined — treat as matching (skip work)
-                        self.update_status(BlockStatus::Ok { was_worked: false });
+                        self.update_status(BlockStatus::Ok {
+                            was_worked: false,
+                            liveness: Liveness::default(),
+                        });
                     }
                     Some(fingerprint) => {
                         let workdir =
@@ -376,7 +398,10 @@ impl BlockProcessor for ServiceBlockContext {
                                         "Fingerprint unchanged ({}), skipping work",
                                         &current_fingerprint
                                     ));
-                                    self.update_status(BlockStatus::Ok { was_worked: false });
+                                    self.update_status(BlockStatus::Ok {
+                                        was_worked: false,
+                                        liveness: Liveness::default(),
+                                    });
                                 } else {
                                     // Fingerprints differ — need to perform work
                                     self.add_system_output(format!(
@@ -388,6 +413,8 @@ impl BlockProcessor for ServiceBlockContext {
                                         step: WorkStep::PerformWork {
                                             current_step_started: Instant::now(),
                                             steps_completed: 0,
+                                            last_failure: None,
+                                            failed_attempts: 0,
                                             new_fingerprint: Some(current_fingerprint),
                                         },
                                     });
@@ -402,6 +429,8 @@ impl BlockProcessor for ServiceBlockContext {
                                     step: WorkStep::PerformWork {
                                         current_step_started: Instant::now(),
                                         steps_completed: 0,
+                                        last_failure: None,
+                                        failed_attempts: 0,
                                         new_fingerprint: None,
                                     },
                                 });
@@ -414,6 +443,8 @@ impl BlockProcessor for ServiceBlockContext {
             WorkStep::PerformWork {
                 steps_completed,
                 current_step_started: step_started,
+                last_failure,
+                failed_attempts,
                 new_fingerprint,
             } => {
                 match self.query_block(|block| block.work.clone()) {
@@ -428,7 +459,8 @@ impl BlockProcessor for ServiceBlockContext {
                                 .collect(),
                             completed_count: steps_completed,
                             entry_start_time: step_started,
-                            last_recoverable_failure: None,
+                            last_recoverable_failure: last_failure,
+                            failed_attempts,
                             context: &context,
                             workdir: self
                                 .query_service(|service| service.definition.workdir.clone()),
@@ -436,16 +468,28 @@ impl BlockProcessor for ServiceBlockContext {
                         .exec_next();
 
                         match result {
-                            // No recoverable failures here, go into error for any kind of issue
-                            WorkExecutionResult::Failed
-                            | WorkExecutionResult::RecoverableFailure => {
+                            WorkExecutionResult::Failed => {
                                 self.update_status(BlockStatus::Error);
                             }
+                            // A command failed but has attempts left
+                            WorkExecutionResult::RecoverableFailure => {
+                                self.update_status(BlockStatus::Working {
+                                    step: WorkStep::PerformWork {
+                                        current_step_started: step_started,
+                                        steps_completed,
+                                        last_failure: Some(Instant::now()),
+                                        failed_attempts: failed_attempts + 1,
+                                        new_fingerprint,
+                                    },
+                                })
+                            }
                             WorkExecutionResult::EntryOk => {
                                 self.update_status(BlockStatus::Working {
                                     step: WorkStep::PerformWork {
                                         current_step_started: Instant::now(),
                                         steps_completed: steps_completed + 1,
+                                        last_failure: None,
+                                        failed_attempts: 0,
                                         new_fingerprint,
                                     },
                                 })
@@ -547,9 +591,14 @@ impl BlockProcessor for ServiceBlockContext {
                             self.store_fingerprint(fp);
                             self.add_system_output(format!("Stored new fingerprint ({})", fp));
                         }
-                        self.update_status(BlockStatus::Ok { was_worked: true })
+                        self.update_status(BlockStatus::Ok {
+                            was_worked: true,
+                            liveness: Liveness::default(),
+                        })
+                    }
+                    RequirementCheckResult::Timeout | RequirementCheckResult::Failed => {
+                        self.update_status(BlockStatus::Error)
                     }
-                    RequirementCheckResult::Timeout => self.update_status(BlockStatus::Error),
                     RequirementCheckResult::CurrentCheckOk => {
                         self.update_status(BlockStatus::Working {
                             step: WorkStep::PostWorkHealthCheck {
@@ -577,6 +626,101 @@ impl BlockProcessor for ServiceBlockContext {
             }
         }
     }
+
+    fn check_liveness(&self, was_worked: bool, liveness: Liveness) {
+        let Some(config) = self.query_block(|block| block.health.liveness.clone()) else {
+            return;
+        };
+
+        if !liveness.checking {
+            if liveness.round_time.elapsed() >= config.interval {
+                self.update_status(BlockStatus::Ok {
+                    was_worked,
+                    liveness: Liveness {
+                        round_time: Instant::now(),
+                        checking: true,
+                        checks_completed: 0,
+                        ..liveness
+                    },
+                });
+            }
+            return;
+        }
+
+        let context = self.create_work_context(OperationType::Check, true);
+        let result = RequirementChecker {
+            all_requirements: config
+                .requirements
+                .unwrap_or_else(|| self.query_block(|block| block.health.requirements.clone())),
+            completed_count: liveness.checks_completed,
+            timeout: None,
+            failure_wait_time: Duration::from_secs(0),
+            start_time: liveness.round_time,
+            last_failure: None,
+            context: &context,
+            workdir: self.query_service(|service| service.definition.workdir.clone()),
+        }
+        .check_requirements();
+
+        match result {
+            RequirementCheckResult::Working => {
+                // Nothing to do, empty on purpose.
+            }
+            RequirementCheckResult::CurrentCheckOk => {
+                self.update_status(BlockStatus::Ok {
+                    was_worked,
+                    liveness: Liveness {
+                        checks_completed: liveness.checks_completed + 1,
+                        ..liveness
+                    },
+                });
+            }
+            RequirementCheckResult::AllOk => {
+                if liveness.consecutive_failures > 0 {
+                    self.add_system_output(format!(
+                        "Liveness checks passed again after {} failed rounds",
+                        liveness.consecutive_failures
+                    ));
+                }
+                self.update_status(BlockStatus::Ok {
+                    was_worked,
+                    liveness: Liveness::default(),
+                });
+            }
+            RequirementCheckResult::CurrentCheckFailed
+            | RequirementCheckResult::Failed
+            | RequirementCheckResult::Timeout => {
+                let consecutive_failures = liveness.consecutive_failures + 1;
+                let unhealthy = consecutive_failures >= config.failure_threshold;
+                self.add_system_output(format!(
+                    "Liveness check failed on requirement {} ({consecutive_failures}/{} failed rounds)",
+                    liveness.checks_completed + 1,
+                    config.failure_threshold
+                ));
+
+                if unhealthy && config.restart {
+                    self.add_system_output("Block is unhealthy, restarting it".to_owned());
+                    let block_id = self.block_id.clone();
+                    self.update_service(|service| {
+                        service.update_block_action(&block_id, Some(BlockAction::ReRun))
+                    });
+                } else if unhealthy && !liveness.unhealthy {
+                    self.add_system_output("Block is unhealthy".to_owned());
+                }
+
+                self.update_status(BlockStatus::Ok {
+                    was_worked,
+                    liveness: Liveness {
+                        round_time: Instant::now(),
+                        checking: false,
+                        checks_completed: 0,
+                        consecutive_failures,
+                        unhealthy,
+                    },
+                });
+            }
+        }
+    }
 }
 
 const PRE_REQ_FAILURE_WAIT: Duration = Duration::from_millis(500);
@@ -0,0 +1,127 @@
+use crate::config::ExecutableEntry;
+use crate::runner::service_worker::WorkResult;
+use log::error;
+use std::io;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, ExitStatus};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Runs the command of a command requirement, succeeding if it exits with one of the given exit codes before the
+/// timeout. The output of the command is included in the result.
+pub fn run_check_command(
+    cmd: Command,
+    entry: &ExecutableEntry,
+    timeout: Duration,
+    exit_codes: &[i32],
+) -> WorkResult {
+    let CommandOutput {
+        exit_status,
+        mut output,
+    } = match run_with_timeout(cmd, timeout) {
+        Ok(result) => result,
+        Err(error) => {
+            return WorkResult {
+                successful: false,
+                output: vec![format!("Req fail: could not run {entry}: {error}")],
+            };
+        }
+    };
+
+    let successful = match exit_status.map(|status| status.code()) {
+        Some(Some(code)) if exit_codes.contains(&code) => {
+            output.push(format!("Req OK: {entry} exited with code {code}"));
+            true
+        }
+        Some(Some(code)) => {
+            output.push(format!("Req fail: {entry} exited with code {code}"));
+            false
+        }
+        Some(None) => {
+            output.push(format!("Req fail: {entry} was terminated by a signal"));
+            false
+        }
+        None => {
+            output.push(format!(
+                "Req fail: {entry} did not finish within {}",
+                humantime::format_duration(timeout)
+            ));
+            false
+        }
+    };
+
+    WorkResult { successful, output }
+}
+
+pub struct CommandOutput {
+    /// Exit status of the command, or `None` if it was killed for exceeding the timeout
+    pub exit_status: Option<ExitStatus>,
+    /// Lines of stdout followed by the lines of stderr
+    pub output: Vec<String>,
+}
+
+/// Runs a command to completion, killing it along with its process group once the timeout is exceeded.
+pub fn run_with_timeout(mut cmd: Command, timeout: Duration) -> io::Result<CommandOutput> {
+    let mut child = cmd.spawn()?;
+
+    // Read the output concurrently, so that the command doesn't block on a full pipe
+    let stdout = child.stdout.take().map(read_lines);
+    let stderr = child.stderr.take().map(read_lines);
+
+    let start = Instant::now();
+    let exit_status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if start.elapsed() >= timeout => {
+                kill_process_group(&mut child);
+                break None;
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(error) => {
+                error!("Failed to wait for check command {cmd:?}: {error}");
+                kill_process_group(&mut child);
+                break None;
+            }
+        }
+    };
+
+    let output = [stdout, stderr]
+        .into_iter()
+        .flatten()
+        .flat_map(|reader| reader.join().unwrap_or_default())
+        .collect();
+
+    Ok(CommandOutput {
+        exit_status,
+        output,
+    })
+}
+
+fn read_lines<R>(stream: R) -> JoinHandle<Vec<String>>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        BufReader::new(stream)
+            .lines()
+            .map_while(Result::ok)
+            .collect()
+    })
+}
+
+fn kill_process_group(child: &mut Child) {
+    #[cfg(target_os = "linux")]
+    {
+        use nix::sys::signal::{self, Signal};
+        use nix::unistd::Pid;
+
+        // Commands are started in their own process group, so this also kills any processes spawned by them
+        if let Err(error) = signal::kill(Pid::from_raw(-(child.id() as i32)), Signal::SIGKILL) {
+            error!("Failed to kill process group of check command: {error:?}");
+        }
+    }
+
+    child.kill().unwrap_or(());
+    child.wait().map(|_| ()).unwrap_or(());
+}
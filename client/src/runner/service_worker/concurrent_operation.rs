@@ -1,6 +1,7 @@
 use std::io::{BufRead, BufReader};
 use std::ops::Neg;
 use std::process::{Child, ExitStatus};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 use std::time::{Duration, Instant};
 use std::{io, thread};
@@ -9,6 +10,7 @@ use log::{error, info};
 
 use crate::config::ServiceId;
 use crate::models::{OutputKey, OutputKind};
+use crate::runner::output_collector::{submit_output, submit_output_end};
 use crate::system_state::SystemState;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -30,6 +32,22 @@ impl ConcurrentOperationHandle {
         }
     }
 
+    /// Output of the operation once it has finished. Only retained for work, as the output of processes is streamed.
+    pub fn output(&self) -> Vec<String> {
+        match self {
+            ConcurrentOperationHandle::Process(_) => Vec::new(),
+            ConcurrentOperationHandle::Work(wrapper) => wrapper.output.lock().unwrap().clone(),
+        }
+    }
+
+    /// Exit code of the process once it has exited, unless it was terminated by a signal. Always `None` for work.
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            ConcurrentOperationHandle::Process(wrapper) => *wrapper.exit_code.lock().unwrap(),
+            ConcurrentOperationHandle::Work(_) => None,
+        }
+    }
+
     /// Signals to this operation that it should stop
     pub fn stop(&self) {
         match self {
@@ -44,6 +62,7 @@ impl ConcurrentOperationHandle {
 
 pub struct WorkWrapper {
     pub status: Arc<Mutex<ConcurrentOperationStatus>>,
+    pub output: Arc<Mutex<Vec<String>>>,
 }
 impl WorkWrapper {
     pub fn wrap<F>(
@@ -58,8 +77,10 @@ impl WorkWrapper {
     {
         let wrapper = WorkWrapper {
             status: Arc::new(Mutex::new(ConcurrentOperationStatus::Running)),
+            output: Arc::new(Mutex::new(Vec::new())),
         };
         let status = wrapper.status.clone();
+        let output = wrapper.output.clone();
         let state_arc_copy = state_arc.clone();
         let output_key = OutputKey {
             service_id: service_id.clone(),
@@ -72,6 +93,7 @@ impl WorkWrapper {
 
         let thread = thread::spawn(move || {
             let result = work();
+            *output.lock().unwrap() = result.output.clone();
 
             if result.successful {
                 *status.lock().unwrap() = ConcurrentOperationStatus::Ok
@@ -108,6 +130,7 @@ pub struct ProcessWrapper {
     pub service_id: Option<ServiceId>,
     pub work_name: String,
     pub status: Arc<Mutex<ConcurrentOperationStatus>>,
+    pub exit_code: Arc<Mutex<Option<i32>>>,
     force_exit: Arc<Mutex<bool>>,
 }
 impl ProcessWrapper {
@@ -123,17 +146,21 @@ impl ProcessWrapper {
             work_name: work_name.clone(),
             force_exit: Arc::new(Mutex::new(false)),
             status: Arc::new(Mutex::new(ConcurrentOperationStatus::Running)),
+            exit_code: Arc::new(Mutex::new(None)),
         };
         let full_name = service_id
             .map(|id| format!("{id}.{work_name}"))
             .unwrap_or(work_name.clone());
 
+        // Both stdout and stderr are written to the same output, which ends once both of them are closed
+        let open_streams = Arc::new(AtomicUsize::new(2));
         let mut new_threads = vec![
             // Kill the process when the server exits and invoke the callback after the process finishes
             (format!("{full_name}-manager"), {
                 let process_handle = handler.handle.clone();
                 let force_exit = handler.force_exit.clone();
                 let status_arc = handler.status.clone();
+                let exit_code_arc = handler.exit_code.clone();
                 let state_arc = state_arc.clone();
 
                 thread::spawn(move || {
@@ -161,6 +188,9 @@ impl ProcessWrapper {
                     let system_exiting = state_arc.read().unwrap().should_exit;
                     let status = Self::kill_process(process_handle, !system_exiting);
                     let success = status.as_ref().is_ok_and(|status| status.success());
+                    // Set before the status, so that the exit code is available once the process is seen as stopped
+                    *exit_code_arc.lock().unwrap() =
+                        status.as_ref().ok().and_then(|status| status.code());
 
                     let mut exit_status = status_arc.lock().unwrap();
                     *exit_status = if success {
@@ -174,6 +204,7 @@ impl ProcessWrapper {
             (format!("{full_name}-stdout"), {
                 let process_handle = handler.handle.clone();
                 let state_arc = state_arc.clone();
+                let open_streams = open_streams.clone();
                 let service_id = handler.service_id.clone();
                 let output_key = OutputKey {
                     service_id: service_id.clone(),
@@ -187,9 +218,21 @@ impl ProcessWrapper {
                         handle.stdout.take().unwrap()
                     };
 
+                    let (output_tx, classifier) = {
+                        let state = state_arc.read().unwrap();
+                        (state.output_tx.clone(), state.output_classifier.clone())
+                    };
                     for line in BufReader::new(stream).lines().flatten() {
-                        let mut state = state_arc.write().unwrap();
-                        state.output_store.add_output(&output_key, line);
+                        submit_output(
+                            &state_arc,
+                            output_tx.as_ref(),
+                            &classifier,
+                            &output_key,
+                            line,
+                        );
+                    }
+                    if open_streams.fetch_sub(1, Ordering::SeqCst) == 1 {
+                        submit_output_end(&state_arc, output_tx.as_ref(), &output_key);
                     }
                 })
             }),
@@ -197,6 +240,7 @@ impl ProcessWrapper {
             (format!("{full_name}-stderr"), {
                 let process_handle = handler.handle.clone();
                 let state_arc = state_arc.clone();
+                let open_streams = open_streams.clone();
                 let service_id = handler.service_id.clone();
                 let output_key = OutputKey {
                     service_id: service_id.clone(),
@@ -210,9 +254,21 @@ impl ProcessWrapper {
                         handle.stderr.take().unwrap()
                     };
 
+                    let (output_tx, classifier) = {
+                        let state = state_arc.read().unwrap();
+                        (state.output_tx.clone(), state.output_classifier.clone())
+                    };
                     for line in BufReader::new(stream).lines().flatten() {
-                        let mut state = state_arc.write().unwrap();
-                        state.output_store.add_output(&output_key, line);
+                        submit_output(
+                            &state_arc,
+                            output_tx.as_ref(),
+                            &classifier,
+                            &output_key,
+                            line,
+                        );
+                    }
+                    if open_streams.fetch_sub(1, Ordering::SeqCst) == 1 {
+                        submit_output_end(&state_arc, output_tx.as_ref(), &output_key);
                     }
                 })
             }),
@@ -32,7 +32,7 @@ impl Display for CmdCreationError {
     }
 }
 
-fn env_subst(value: &str) -> Result<String, CmdCreationError> {
+pub fn env_subst(value: &str) -> Result<String, CmdCreationError> {
     subst::substitute(value, &subst::Env).map_err(|err| match err {
         Error::InvalidEscapeSequence(InvalidEscapeSequence { position, .. }) => {
             MalformattedExpression {
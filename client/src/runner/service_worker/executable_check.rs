@@ -0,0 +1,160 @@
+use crate::config::{ExecutableEntry, ExecutableRequirement};
+use crate::runner::service_worker::WorkResult;
+use crate::runner::service_worker::command_check::{CommandOutput, run_with_timeout};
+use crate::runner::service_worker::create_cmd::{create_cmd, env_subst};
+use regex::Regex;
+use semver::{Version, VersionReq};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Looks up the executable of the requirement and, if a version is required, runs it to compare the version it
+/// reports.
+pub fn check_executable(requirement: &ExecutableRequirement, workdir: &str) -> WorkResult {
+    let failure = |message: String| WorkResult {
+        successful: false,
+        output: vec![message],
+    };
+    let name = &requirement.executable;
+
+    let path = match resolve_executable(name, workdir) {
+        Ok(path) => path,
+        Err(message) => return failure(format!("Req fail: {message}")),
+    };
+    if requirement.version.is_none() && requirement.version_pattern.is_none() {
+        return WorkResult {
+            successful: true,
+            output: vec![format!("Req OK: found {name} at {}", path.display())],
+        };
+    }
+
+    let entry = ExecutableEntry {
+        executable: path.to_string_lossy().into_owned(),
+        args: requirement.version_args.clone(),
+        env: HashMap::new(),
+    };
+    let output = create_cmd(&entry, Some(workdir))
+        .map_err(|error| error.to_string())
+        .and_then(|cmd| run_with_timeout(cmd, requirement.timeout).map_err(|e| e.to_string()));
+    let version_output = match output {
+        Ok(CommandOutput {
+            exit_status: Some(_),
+            output,
+        }) => output.join("\n"),
+        Ok(CommandOutput {
+            exit_status: None, ..
+        }) => {
+            return failure(format!(
+                "Req fail: {entry} did not finish within {}",
+                humantime::format_duration(requirement.timeout)
+            ));
+        }
+        Err(error) => return failure(format!("Req fail: could not run {entry}: {error}")),
+    };
+
+    let mut failures = Vec::new();
+    if let Some(version_req) = &requirement.version {
+        match (
+            VersionReq::parse(version_req),
+            find_version(&version_output),
+        ) {
+            (Ok(version_req), Some(version)) if version_req.matches(&version) => {}
+            (Ok(_), Some(version)) => {
+                failures.push(format!("version {version} does not match '{version_req}'"))
+            }
+            (Ok(_), None) => failures.push(format!("no version number in the output of {entry}")),
+            (Err(error), _) => failures.push(format!("invalid version requirement: {error}")),
+        }
+    }
+    if let Some(pattern) = &requirement.version_pattern {
+        match Regex::new(pattern) {
+            Ok(regex) if regex.is_match(&version_output) => {}
+            Ok(_) => failures.push(format!("output of {entry} does not match '{pattern}'")),
+            Err(error) => failures.push(format!("invalid version pattern: {error}")),
+        }
+    }
+
+    if failures.is_empty() {
+        WorkResult {
+            successful: true,
+            output: vec![format!(
+                "Req OK: found {name} at {}, reporting '{}'",
+                path.display(),
+                version_output.lines().next().unwrap_or_default()
+            )],
+        }
+    } else {
+        WorkResult {
+            successful: false,
+            output: failures
+                .into_iter()
+                .map(|failure| format!("Req fail: {name}: {failure}"))
+                .collect(),
+        }
+    }
+}
+
+/// Resolves an executable the same way a shell would: names containing a slash are paths, relative to the workdir,
+/// while other names are searched for in the directories of `PATH`.
+fn resolve_executable(name: &str, workdir: &str) -> Result<PathBuf, String> {
+    let name = env_subst(name).map_err(|error| error.to_string())?;
+    if name.contains('/') {
+        let path = Path::new(workdir).join(&name);
+        return if is_executable(&path) {
+            Ok(path)
+        } else if path.exists() {
+            Err(format!("{} is not executable", path.display()))
+        } else {
+            Err(format!("{} does not exist", path.display()))
+        };
+    }
+
+    env::var_os("PATH")
+        .iter()
+        .flat_map(env::split_paths)
+        .map(|dir| dir.join(&name))
+        .find(|path| is_executable(path))
+        .ok_or_else(|| format!("{name} was not found on PATH"))
+}
+
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+/// Finds the first version number in the output of a version command. Versions lacking a minor or patch component,
+/// such as `21` or `1.8`, are padded with zeroes.
+fn find_version(output: &str) -> Option<Version> {
+    let regex = Regex::new(r"(\d+)(?:\.(\d+))?(?:\.(\d+))?").unwrap();
+    let captures = regex.captures(output)?;
+    let component = |index| {
+        captures
+            .get(index)
+            .map_or(Some(0), |component| component.as_str().parse().ok())
+    };
+
+    Some(Version::new(component(1)?, component(2)?, component(3)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_version;
+    use semver::Version;
+
+    #[test]
+    fn test_find_version() {
+        assert_eq!(
+            find_version("openjdk 21 2023-09-19\nOpenJDK Runtime Environment (build 21+35-2513)"),
+            Some(Version::new(21, 0, 0))
+        );
+        assert_eq!(find_version("v18.17.1"), Some(Version::new(18, 17, 1)));
+        assert_eq!(
+            find_version("go version go1.21.5 linux/amd64"),
+            Some(Version::new(1, 21, 5))
+        );
+        assert_eq!(find_version("Python 3.12"), Some(Version::new(3, 12, 0)));
+        assert_eq!(find_version("no version here"), None);
+    }
+}
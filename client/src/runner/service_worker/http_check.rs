@@ -0,0 +1,130 @@
+use crate::config::{HttpMethod, HttpRequirement};
+use crate::runner::service_worker::WorkResult;
+use regex::Regex;
+use reqwest::Method;
+use reqwest::blocking::Client as HttpClient;
+
+/// Performs the request of an HTTP requirement and checks the response against it. Every failed assertion is
+/// reported in the output of the result.
+pub fn check_http(requirement: &HttpRequirement) -> WorkResult {
+    let HttpRequirement { method, url, .. } = requirement;
+    let failure = |message: String| WorkResult {
+        successful: false,
+        output: vec![message],
+    };
+
+    let http_client = match HttpClient::builder()
+        .danger_accept_invalid_certs(requirement.insecure)
+        .build()
+    {
+        Ok(client) => client,
+        Err(error) => return failure(format_reqwest_error(&error)),
+    };
+    let mut request = http_client
+        .request(
+            match method {
+                HttpMethod::GET => Method::GET,
+                HttpMethod::HEAD => Method::HEAD,
+                HttpMethod::POST => Method::POST,
+                HttpMethod::PUT => Method::PUT,
+                HttpMethod::PATCH => Method::PATCH,
+                HttpMethod::DELETE => Method::DELETE,
+                HttpMethod::OPTIONS => Method::OPTIONS,
+            },
+            url,
+        )
+        .timeout(requirement.timeout);
+    for (name, value) in &requirement.headers {
+        request = request.header(name, value);
+    }
+    if let Some(body) = &requirement.body {
+        request = request.body(body.clone());
+    }
+
+    let response = match request.send() {
+        Ok(response) => response,
+        Err(error) => return failure(format_reqwest_error(&error)),
+    };
+    let status = response.status().as_u16();
+    if !requirement.status.matches(status) {
+        return failure(format!(
+            "Req fail: {method} {url} responded with status {status}, expected {expected}",
+            expected = requirement.status
+        ));
+    }
+
+    let mut failures = Vec::new();
+    if requirement.body_pattern.is_some() || !requirement.json.is_empty() {
+        let body = match response.text() {
+            Ok(body) => body,
+            Err(error) => return failure(format_reqwest_error(&error)),
+        };
+
+        if let Some(pattern) = &requirement.body_pattern {
+            match Regex::new(pattern) {
+                Ok(regex) if regex.is_match(&body) => {}
+                Ok(_) => failures.push(format!("body does not match '{pattern}'")),
+                Err(error) => failures.push(format!("invalid body pattern: {error}")),
+            }
+        }
+
+        if !requirement.json.is_empty() {
+            match serde_json::from_str::<serde_json::Value>(&body) {
+                Ok(json) => {
+                    for (pointer, expected) in &requirement.json {
+                        match json.pointer(pointer) {
+                            Some(actual) if actual == expected => {}
+                            Some(actual) => {
+                                failures.push(format!("{pointer} is {actual}, expected {expected}"))
+                            }
+                            None => failures.push(format!("{pointer} is missing from the body")),
+                        }
+                    }
+                }
+                Err(error) => failures.push(format!("body is not valid JSON: {error}")),
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        WorkResult {
+            successful: true,
+            output: vec![format!(
+                "Req OK: {method} {url} responded with status {status}"
+            )],
+        }
+    } else {
+        WorkResult {
+            successful: false,
+            output: failures
+                .into_iter()
+                .map(|failure| format!("Req fail: {method} {url}: {failure}"))
+                .collect(),
+        }
+    }
+}
+
+/// Turns a `reqwest::Error` into a clean, human-readable string.
+pub fn format_reqwest_error(err: &reqwest::Error) -> String {
+    if err.is_connect() {
+        return format!("Connection error: {}", err);
+    }
+    if err.is_timeout() {
+        return "Request timed out.".to_string();
+    }
+    if err.is_request() {
+        return format!("Request build error: {}", err);
+    }
+    if err.is_body() {
+        return format!("Body error: {}", err);
+    }
+    if err.is_decode() {
+        return format!("Response decoding error: {}", err);
+    }
+    if err.is_builder() {
+        return format!("Client build error: {}", err);
+    }
+
+    // Fall back to a generic error message
+    format!("Unexpected error: {}", err)
+}
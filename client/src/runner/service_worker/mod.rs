@@ -1,9 +1,10 @@
 use crate::config::{AutomationAction, AutomationDefinitionId, ServiceId};
-use crate::models::TaskStatus;
+use crate::models::{PreflightStatus, TaskStatus};
 use crate::runner::query_trigger_handler::QueryTriggerHandler;
 use crate::runner::scripting::executor::ScriptExecutor;
 use crate::runner::service_worker::ConcurrentOperationStatus;
 use crate::runner::service_worker::block_processor::BlockProcessor;
+use crate::runner::service_worker::executable_check::check_executable;
 use crate::runner::service_worker::service_block_context::ServiceBlockContext;
 use crate::runner::service_worker::task_context::TaskContext;
 use crate::runner::service_worker::task_processor::TaskProcessor;
@@ -16,12 +17,18 @@ use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 
 mod block_processor;
+mod command_check;
 mod concurrent_operation;
 mod create_cmd;
+mod executable_check;
+mod http_check;
+mod port_check;
+mod prepared_check;
 mod requirement_checker;
 mod service_block_context;
 mod task_context;
 mod task_processor;
+mod unix_socket_check;
 mod work_context;
 mod work_sequence_executor;
 
@@ -48,6 +55,7 @@ impl ServiceWorker {
             let mut query_trigger_handler = QueryTriggerHandler::new(state.clone());
 
             while *keep_running.lock().unwrap() {
+                Self::run_preflight_checks(state.clone());
                 Self::work_services(state.clone(), executor.clone());
                 query_trigger_handler.process_automation_triggers();
                 Self::spawn_automation_tasks(state.clone());
@@ -122,6 +130,66 @@ impl ServiceWorker {
             });
     }
 
+    /// Starts the pending checks of the profile being activated, each on its own thread so that a slow version command
+    /// doesn't hold up the others.
+    fn run_preflight_checks(state_arc: Arc<RwLock<SystemState>>) {
+        let mut state = state_arc.write().unwrap();
+        let Some(preflight) = state.preflight.as_mut() else {
+            return;
+        };
+
+        let mut threads = Vec::new();
+        for (index, check) in preflight.checks.iter_mut().enumerate() {
+            if !matches!(check.status, PreflightStatus::Pending) {
+                continue;
+            }
+            check.status = PreflightStatus::Running;
+
+            let state_arc = state_arc.clone();
+            let alternatives = check.alternatives.clone();
+            let workdir = check.workdir.clone();
+            let thread_name = format!(
+                "preflight-{}",
+                alternatives
+                    .first()
+                    .map(|requirement| requirement.executable.as_str())
+                    .unwrap_or_default()
+            );
+            let thread = thread::spawn(move || {
+                // The alternatives are checked in order until one of them is found
+                let mut result = WorkResult {
+                    successful: false,
+                    output: Vec::new(),
+                };
+                for requirement in &alternatives {
+                    let alternative = check_executable(requirement, &workdir);
+                    if alternative.successful {
+                        result = alternative;
+                        break;
+                    }
+                    result.output.extend(alternative.output);
+                }
+                let mut state = state_arc.write().unwrap();
+                // The activation might have been cancelled, or even restarted, in the meantime
+                if let Some(check) = state
+                    .preflight
+                    .as_mut()
+                    .and_then(|preflight| preflight.checks.get_mut(index))
+                    .filter(|check| check.alternatives == alternatives)
+                {
+                    check.status = if result.successful {
+                        PreflightStatus::Ok(result.output.join(", "))
+                    } else {
+                        PreflightStatus::Failed(result.output)
+                    };
+                }
+            });
+            threads.push((thread_name, thread));
+        }
+
+        state.active_threads.append(&mut threads);
+    }
+
     fn spawn_automation_tasks(state_arc: Arc<RwLock<SystemState>>) {
         struct TriggerableAutomation {
             automation_id: AutomationDefinitionId,
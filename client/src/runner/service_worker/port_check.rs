@@ -0,0 +1,171 @@
+use std::fs;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// A socket listening on a local TCP port, as found in `/proc/net/tcp`.
+pub struct PortOwner {
+    pub uid: u32,
+    /// The process holding the socket. Finding it requires access to the file descriptors of the process, which is
+    /// usually only granted for processes of the same user.
+    pub process: Option<PortOwnerProcess>,
+}
+impl PortOwner {
+    /// Whether the socket is held by a process outside the given process groups.
+    pub fn is_stranger(&self, own_process_groups: &[u32]) -> bool {
+        self.process
+            .as_ref()
+            .is_none_or(|process| !own_process_groups.contains(&process.pgid))
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.process {
+            Some(process) => format!("{} (pid {})", process.name, process.pid),
+            None => format!("a process of uid {}", self.uid),
+        }
+    }
+}
+
+pub struct PortOwnerProcess {
+    pub pid: u32,
+    pub name: String,
+    pub pgid: u32,
+}
+
+/// Connects to the addresses the host resolves to until a connection succeeds, returning the address connected to.
+/// Resolution goes through the system resolver, so entries of `/etc/hosts` are respected.
+pub fn connect(host: &str, port: u16, timeout: Duration) -> Result<SocketAddr, String> {
+    let addresses: Vec<SocketAddr> = (host, port)
+        .to_socket_addrs()
+        .map_err(|err| format!("could not resolve {host}: {err}"))?
+        .collect();
+    if addresses.is_empty() {
+        return Err(format!("{host} did not resolve to any address"));
+    }
+
+    let mut errors = Vec::new();
+    for address in addresses {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(_) => return Ok(address),
+            Err(err) => errors.push(format!("{address}: {err}")),
+        }
+    }
+    Err(errors.join(", "))
+}
+
+/// Whether the host refers to this machine, in which case the owner of a port can be looked up.
+pub fn is_local_host(host: &str) -> bool {
+    (host, 0).to_socket_addrs().is_ok_and(|mut addresses| {
+        addresses.any(|address| address.ip().is_loopback() || address.ip().is_unspecified())
+    })
+}
+
+/// Finds the socket listening on the given TCP port of this machine. Only supported on Linux.
+pub fn find_port_owner(port: u16) -> Option<PortOwner> {
+    let (inode, uid) = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .find_map(|table| find_listening_socket(&table, port))?;
+
+    Some(PortOwner {
+        uid,
+        process: find_socket_process(inode),
+    })
+}
+
+/// Returns the inode and uid of the socket listening on the port from the contents of `/proc/net/tcp`.
+fn find_listening_socket(table: &str, port: u16) -> Option<(u64, u32)> {
+    table.lines().skip(1).find_map(|line| {
+        // Columns: sl, local_address, rem_address, st, tx_queue:rx_queue, tr:tm->when, retrnsmt, uid, timeout, inode
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let local_port = u16::from_str_radix(columns.get(1)?.rsplit(':').next()?, 16).ok()?;
+        // State 0A is LISTEN
+        if local_port != port || *columns.get(3)? != "0A" {
+            return None;
+        }
+        Some((columns.get(9)?.parse().ok()?, columns.get(7)?.parse().ok()?))
+    })
+}
+
+fn find_socket_process(inode: u64) -> Option<PortOwnerProcess> {
+    let socket_link = format!("socket:[{inode}]");
+
+    fs::read_dir("/proc").ok()?.flatten().find_map(|entry| {
+        let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+        let holds_socket = fs::read_dir(entry.path().join("fd"))
+            .ok()?
+            .flatten()
+            .any(|fd| {
+                fs::read_link(fd.path()).is_ok_and(|link| link.as_os_str() == socket_link.as_str())
+            });
+        if !holds_socket {
+            return None;
+        }
+
+        // The name is enclosed in parentheses and may contain spaces itself. It is followed by the state, the parent
+        // pid and the process group.
+        let stat = fs::read_to_string(entry.path().join("stat")).ok()?;
+        let (name, rest) = stat.split_once('(')?.1.rsplit_once(')')?;
+        Some(PortOwnerProcess {
+            pid,
+            name: name.to_string(),
+            pgid: rest.split_whitespace().nth(2)?.parse().ok()?,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_listening_socket, find_port_owner, is_local_host};
+    use std::net::TcpListener;
+    use std::path::Path;
+
+    const TCP: &str = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1F90 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 234567 1 0000000000000000 20 4 30 10 -1
+   1: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 123456 1 0000000000000000 100 0 0 10 0
+   2: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 4711 1 0000000000000000 100 0 0 10 0
+";
+    const TCP6: &str = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:0BB8 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000   501        0 345678 1 0000000000000000 100 0 0 10 0
+   1: 00000000000000000000000001000000:C350 00000000000000000000000001000000:0BB8 01 00000000:00000000 00:00000000 00000000   501        0 456789 1 0000000000000000 20 4 30 10 -1
+";
+
+    #[test]
+    fn test_find_listening_socket() {
+        // Connections on the port are skipped in favor of the listening socket
+        assert_eq!(find_listening_socket(TCP, 8080), Some((123456, 1000)));
+        assert_eq!(find_listening_socket(TCP, 22), Some((4711, 0)));
+        assert_eq!(find_listening_socket(TCP6, 3000), Some((345678, 501)));
+        // Only the local port counts, and only listening sockets
+        assert_eq!(find_listening_socket(TCP, 54321), None);
+        assert_eq!(find_listening_socket(TCP6, 50000), None);
+        assert_eq!(find_listening_socket("", 8080), None);
+    }
+
+    #[test]
+    fn test_find_port_owner() {
+        if !Path::new("/proc/net/tcp").exists() {
+            return;
+        }
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let owner = find_port_owner(port).expect("the port has an owner");
+        let process = owner
+            .process
+            .as_ref()
+            .expect("the process of the socket is found");
+        assert_eq!(process.pid, std::process::id());
+        assert!(!owner.is_stranger(&[process.pgid]));
+        assert!(owner.is_stranger(&[]));
+    }
+
+    #[test]
+    fn test_is_local_host() {
+        assert!(is_local_host("127.0.0.1"));
+        assert!(is_local_host("::1"));
+        assert!(is_local_host("0.0.0.0"));
+        assert!(is_local_host("localhost"));
+        assert!(!is_local_host("192.0.2.1"));
+        assert!(!is_local_host("2001:db8::1"));
+    }
+}
@@ -0,0 +1,488 @@
+use crate::config::{
+    ExecutableEntry, ExecutableRequirement, HttpRequirement, PortCheckMode, UnixSocketHttp,
+};
+use crate::runner::service_worker::WorkResult;
+use crate::runner::service_worker::command_check::run_check_command;
+use crate::runner::service_worker::create_cmd::CmdCreationError;
+use crate::runner::service_worker::executable_check::check_executable;
+use crate::runner::service_worker::http_check::check_http;
+use crate::runner::service_worker::port_check;
+use crate::runner::service_worker::unix_socket_check::check_unix_socket;
+use rhai::plugin::RhaiResult;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// A requirement along with everything needed to check it, so that it can be checked off the worker thread.
+pub enum PreparedCheck {
+    Http(HttpRequirement),
+    Port {
+        host: String,
+        port: u16,
+        mode: PortCheckMode,
+        timeout: Duration,
+        /// Process groups of the service, used to tell whether a process holding the port belongs to it
+        own_process_groups: Vec<u32>,
+    },
+    File {
+        paths: Vec<String>,
+        workdir: String,
+        newer_than: Vec<String>,
+        /// Time that the files have to be modified after, such as the start of the block
+        not_before: Option<SystemTime>,
+    },
+    UnixSocket {
+        path: PathBuf,
+        http: Option<UnixSocketHttp>,
+        timeout: Duration,
+    },
+    StateQuery {
+        query: String,
+        result_rx: Receiver<RhaiResult>,
+    },
+    Command {
+        cmd: Result<Command, CmdCreationError>,
+        entry: ExecutableEntry,
+        timeout: Duration,
+        exit_codes: Vec<i32>,
+    },
+    Executable {
+        requirement: ExecutableRequirement,
+        workdir: String,
+    },
+    /// A check whose result was already determined while preparing it
+    Done(WorkResult),
+    Any {
+        branches: Vec<PreparedCheck>,
+        timeout: Duration,
+    },
+    All {
+        branches: Vec<PreparedCheck>,
+        timeout: Duration,
+    },
+    Not {
+        branch: Box<PreparedCheck>,
+        timeout: Duration,
+    },
+}
+impl PreparedCheck {
+    pub fn run(self) -> WorkResult {
+        match self {
+            PreparedCheck::Http(requirement) => check_http(&requirement),
+            PreparedCheck::Port {
+                host,
+                port,
+                mode,
+                timeout,
+                own_process_groups,
+            } => check_port(&host, port, mode, timeout, &own_process_groups),
+            PreparedCheck::File {
+                paths,
+                workdir,
+                newer_than,
+                not_before,
+            } => check_files(paths, &workdir, &newer_than, not_before),
+            PreparedCheck::UnixSocket {
+                path,
+                http,
+                timeout,
+            } => check_unix_socket(&path, http.as_ref(), timeout),
+            PreparedCheck::StateQuery { query, result_rx } => match result_rx.recv() {
+                Ok(Ok(value)) if value.is::<bool>() => WorkResult {
+                    successful: value.as_bool().unwrap(),
+                    output: vec![format!("Query '{query}' => {value}")],
+                },
+                Ok(Ok(value)) => WorkResult {
+                    successful: false,
+                    output: vec![format!(
+                        "Error: Query outputted non-boolean: '{query}' => {value}"
+                    )],
+                },
+                Ok(Err(error)) => WorkResult {
+                    successful: false,
+                    output: vec![format!("Error in Rhai query {query}: {error:?}")],
+                },
+                Err(error) => WorkResult {
+                    successful: false,
+                    output: vec![format!(
+                        "Error in receiving response from Rhai executor: {error:?}"
+                    )],
+                },
+            },
+            PreparedCheck::Command {
+                cmd,
+                entry,
+                timeout,
+                exit_codes,
+            } => match cmd {
+                Ok(cmd) => run_check_command(cmd, &entry, timeout, &exit_codes),
+                Err(error) => WorkResult {
+                    successful: false,
+                    output: vec![format!("Req fail: {error}")],
+                },
+            },
+            PreparedCheck::Executable {
+                requirement,
+                workdir,
+            } => check_executable(&requirement, &workdir),
+            PreparedCheck::Done(result) => result,
+            PreparedCheck::Any { branches, timeout } => {
+                let count = branches.len();
+                let mut output = Vec::new();
+                for (index, branch) in branches.into_iter().enumerate() {
+                    let result = run_branch(branch, timeout);
+                    if result.successful {
+                        output.insert(
+                            0,
+                            format!("Req OK: alternative {} of {count} succeeded", index + 1),
+                        );
+                        output.extend(indent_branch_output(index, result.output));
+                        return WorkResult {
+                            successful: true,
+                            output,
+                        };
+                    }
+                    output.extend(indent_branch_output(index, result.output));
+                }
+
+                output.insert(
+                    0,
+                    format!("Req fail: none of {count} alternatives succeeded"),
+                );
+                WorkResult {
+                    successful: false,
+                    output,
+                }
+            }
+            PreparedCheck::All { branches, timeout } => {
+                let count = branches.len();
+                let mut output = Vec::new();
+                for (index, branch) in branches.into_iter().enumerate() {
+                    let result = run_branch(branch, timeout);
+                    output.extend(indent_branch_output(index, result.output));
+                    if !result.successful {
+                        output.insert(
+                            0,
+                            format!("Req fail: requirement {} of {count} failed", index + 1),
+                        );
+                        return WorkResult {
+                            successful: false,
+                            output,
+                        };
+                    }
+                }
+
+                output.insert(0, format!("Req OK: all {count} requirements succeeded"));
+                WorkResult {
+                    successful: true,
+                    output,
+                }
+            }
+            PreparedCheck::Not { branch, timeout } => {
+                let result = run_branch(*branch, timeout);
+                let mut output = vec![if result.successful {
+                    "Req fail: negated requirement succeeded".to_string()
+                } else {
+                    "Req OK: negated requirement failed".to_string()
+                }];
+                output.extend(indent_branch_output(0, result.output));
+                WorkResult {
+                    successful: !result.successful,
+                    output,
+                }
+            }
+        }
+    }
+}
+
+/// Runs a branch of a combinator, counting it as failed if it takes longer than the timeout. A timed out branch keeps
+/// running in the background until its own timeouts end it, but its result is ignored.
+fn run_branch(branch: PreparedCheck, timeout: Duration) -> WorkResult {
+    let (result_tx, result_rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = result_tx.send(branch.run());
+    });
+
+    result_rx
+        .recv_timeout(timeout)
+        .unwrap_or_else(|_| WorkResult {
+            successful: false,
+            output: vec![format!(
+                "Req fail: timed out after {}",
+                humantime::format_duration(timeout)
+            )],
+        })
+}
+
+fn indent_branch_output(index: usize, output: Vec<String>) -> Vec<String> {
+    output
+        .into_iter()
+        .enumerate()
+        .map(|(line_index, line)| {
+            if line_index == 0 {
+                format!("  [{}] {line}", index + 1)
+            } else {
+                format!("      {line}")
+            }
+        })
+        .collect()
+}
+
+fn check_port(
+    host: &str,
+    port: u16,
+    mode: PortCheckMode,
+    timeout: Duration,
+    own_process_groups: &[u32],
+) -> WorkResult {
+    let result = match mode {
+        PortCheckMode::Bind => match TcpListener::bind(format!("{host}:{port}")) {
+            Ok(_) => Err(format!(
+                "Req fail: could bind to {host}:{port}, so nothing is listening on it"
+            )),
+            Err(_) => Ok(format!("Req OK: {host}:{port} is in use")),
+        },
+        PortCheckMode::Connect => match port_check::connect(host, port, timeout) {
+            Ok(address) => Ok(format!("Req OK: connected to {host}:{port} ({address})")),
+            Err(error) => Err(format!(
+                "Req fail: could not connect to {host}:{port}: {error}"
+            )),
+        },
+    };
+
+    match result {
+        Ok(message) => {
+            let mut output = vec![message];
+            // A process unrelated to the service holding the port usually means that the service failed to start, so
+            // point it out
+            if port_check::is_local_host(host)
+                && let Some(owner) = port_check::find_port_owner(port)
+                && owner.is_stranger(own_process_groups)
+            {
+                output.push(format!(
+                    "Warning: port {port} is held by {owner}, which was not started by this service",
+                    owner = owner.describe()
+                ));
+            }
+            WorkResult {
+                successful: true,
+                output,
+            }
+        }
+        Err(message) => WorkResult {
+            successful: false,
+            output: vec![message],
+        },
+    }
+}
+
+fn check_files(
+    paths: Vec<String>,
+    workdir: &str,
+    newer_than: &[String],
+    not_before: Option<SystemTime>,
+) -> WorkResult {
+    let mut output = Vec::new();
+    let mut success = true;
+    let mut matched_files = Vec::new();
+
+    for path in paths {
+        match glob_paths(&path, workdir) {
+            Ok((pattern_str, matches)) => {
+                if !matches.is_empty() {
+                    output.push(format!("Req OK: path {} exists", pattern_str));
+                    matched_files.extend(matches);
+                } else {
+                    output.push(format!(
+                        "Req fail: no file/dir found with '{}'",
+                        pattern_str
+                    ));
+                    success = false;
+                }
+            }
+            Err(message) => {
+                output.push(message);
+                success = false;
+            }
+        }
+    }
+
+    if success && (!newer_than.is_empty() || not_before.is_some()) {
+        match check_freshness(&matched_files, workdir, newer_than, not_before) {
+            Ok(message) => output.push(message),
+            Err(message) => {
+                output.push(message);
+                success = false;
+            }
+        }
+    }
+
+    WorkResult {
+        successful: success,
+        output,
+    }
+}
+
+/// Checks that every file was modified after the newest of the files matching `newer_than` and after `not_before`.
+fn check_freshness(
+    files: &[PathBuf],
+    workdir: &str,
+    newer_than: &[String],
+    not_before: Option<SystemTime>,
+) -> Result<String, String> {
+    let mut newest_reference: Option<(SystemTime, String)> =
+        not_before.map(|time| (time, "the start of the block".to_string()));
+    for pattern in newer_than {
+        let (_, matches) = glob_paths(pattern, workdir)?;
+        for path in matches {
+            let modified = modified_time(&path)?;
+            if newest_reference
+                .as_ref()
+                .is_none_or(|(newest, _)| modified > *newest)
+            {
+                newest_reference = Some((modified, path.display().to_string()));
+            }
+        }
+    }
+
+    let Some((reference_time, reference)) = newest_reference else {
+        return Ok("Req OK: no files to compare modification times with".to_string());
+    };
+    for file in files {
+        if modified_time(file)? <= reference_time {
+            return Err(format!(
+                "Req fail: {} is not newer than {reference}",
+                file.display()
+            ));
+        }
+    }
+
+    Ok(format!("Req OK: all files are newer than {reference}"))
+}
+
+fn modified_time(path: &Path) -> Result<SystemTime, String> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| {
+            format!(
+                "Req fail: could not read modification time of {}: {}",
+                path.display(),
+                e
+            )
+        })
+}
+
+/// Expands a glob, relative to the workdir unless absolute, returning the pattern along with the existing paths it
+/// matches.
+fn glob_paths(path: &str, workdir: &str) -> Result<(String, Vec<PathBuf>), String> {
+    // Resolve real path: if not absolute, join with workdir
+    let pattern_path = Path::new(path);
+    let full_pattern = if pattern_path.is_absolute() {
+        pattern_path.to_path_buf()
+    } else {
+        Path::new(workdir).join(pattern_path)
+    };
+
+    // Convert to string for glob; we don't canonicalize because the user wants glob expansion.
+    let pattern_str = match full_pattern.to_str() {
+        Some(s) => s.to_owned(),
+        None => {
+            return Err(format!(
+                "Could not convert path to string: {:?}. This indicates a bug in the runner",
+                full_pattern
+            ));
+        }
+    };
+
+    match glob::glob(&pattern_str) {
+        Ok(entries) => {
+            let mut matches = Vec::new();
+            for entry in entries {
+                match entry {
+                    Ok(path) => {
+                        if path.exists() {
+                            matches.push(path);
+                        }
+                    }
+                    Err(e) => {
+                        return Err(format!(
+                            "Req fail: unexpected IO error when checking {}: {}",
+                            pattern_str, e
+                        ));
+                    }
+                }
+            }
+            Ok((pattern_str, matches))
+        }
+        Err(_) => Err(format!("Req fail: invalid glob pattern '{}'", pattern_str)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_freshness;
+    use std::fs::File;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_check_freshness() {
+        let dir = std::env::temp_dir().join(format!("freshness-check-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        let workdir = dir.to_str().unwrap();
+        let now = SystemTime::now();
+        let touch = |name: &str, age_secs: u64| -> PathBuf {
+            let path = dir.join(name);
+            File::create(&path)
+                .unwrap()
+                .set_modified(now - Duration::from_secs(age_secs))
+                .unwrap();
+            path
+        };
+        touch("src/main.c", 300);
+        touch("src/util.c", 200);
+        let artifact = touch("app", 100);
+        let stale = touch("lib.a", 250);
+        let sources = vec!["src/*.c".to_string()];
+
+        assert!(check_freshness(std::slice::from_ref(&artifact), workdir, &sources, None).is_ok());
+        assert_eq!(
+            check_freshness(&[artifact.clone(), stale.clone()], workdir, &sources, None),
+            Err(format!(
+                "Req fail: {} is not newer than {}",
+                stale.display(),
+                dir.join("src/util.c").display()
+            ))
+        );
+        // The start of the block counts if it's newer than the sources
+        assert!(
+            check_freshness(
+                std::slice::from_ref(&artifact),
+                workdir,
+                &sources,
+                Some(now - Duration::from_secs(150))
+            )
+            .is_ok()
+        );
+        assert_eq!(
+            check_freshness(
+                std::slice::from_ref(&artifact),
+                workdir,
+                &sources,
+                Some(now - Duration::from_secs(50))
+            ),
+            Err(format!(
+                "Req fail: {} is not newer than the start of the block",
+                artifact.display()
+            ))
+        );
+        // Without any reference, any file is fresh
+        assert!(check_freshness(&[stale], workdir, &["none/*".to_string()], None).is_ok());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
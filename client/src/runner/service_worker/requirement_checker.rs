@@ -1,17 +1,22 @@
-use crate::config::{HttpMethod, Requirement};
+use crate::config::{BlockId, Requirement, ServiceId};
+use crate::models::{OutputKey, OutputKind, OutputLine};
+use crate::runner::service_worker::create_cmd::create_cmd;
+use crate::runner::service_worker::prepared_check::PreparedCheck;
 use crate::runner::service_worker::work_context::WorkContext;
 use crate::runner::service_worker::{ConcurrentOperationStatus, WorkResult};
-use reqwest::Method;
-use reqwest::blocking::Client as HttpClient;
-use std::net::TcpListener;
+use chrono::{DateTime, Local};
+use regex::Regex;
+use std::collections::VecDeque;
 use std::path::Path;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 pub enum RequirementCheckResult {
     AllOk,
     CurrentCheckOk,
     Working,
     CurrentCheckFailed,
+    /// The current check failed in a way that retrying it can't fix
+    Failed,
     Timeout,
 }
 
@@ -61,6 +66,18 @@ impl<'a, W: WorkContext> RequirementChecker<'a, W> {
                 // checks are not constantly spammed.
                 RequirementCheckResult::Working
             }
+            // Output patterns are matched right away, as a line matching the failure pattern fails the check without
+            // retrying it
+            (
+                None,
+                Some(Requirement::OutputPattern {
+                    pattern,
+                    failure_pattern,
+                    service,
+                    source,
+                }),
+                _,
+            ) => self.check_output_pattern(&pattern, failure_pattern.as_deref(), service, source),
             // No ongoing check, still have requirements to check => start the next check
             (None, Some(requirement), _) => {
                 self.check_requirement(&requirement);
@@ -69,6 +86,7 @@ impl<'a, W: WorkContext> RequirementChecker<'a, W> {
             // A check has failed, but we have not yet exceeded the timeout. Clear the operation and returned
             // corresponding status
             (Some(ConcurrentOperationStatus::Failed), _, _) => {
+                self.record_failure(self.context.get_concurrent_operation_output());
                 self.context.clear_concurrent_operation();
                 RequirementCheckResult::CurrentCheckFailed
             }
@@ -85,191 +103,431 @@ impl<'a, W: WorkContext> RequirementChecker<'a, W> {
     }
 
     fn check_requirement(&self, requirement: &Requirement) {
+        let check = self.prepare_check(requirement);
+        self.context.perform_concurrent_work(move || check.run());
+    }
+
+    /// Resolves everything needed for checking the requirement that depends on the context, so that the check can
+    /// be run on another thread.
+    fn prepare_check(&self, requirement: &Requirement) -> PreparedCheck {
         match requirement.clone() {
-            Requirement::Http {
-                url,
-                method,
+            Requirement::Http(requirement) => PreparedCheck::Http(requirement),
+            Requirement::Port {
+                port,
+                host,
+                mode,
+                timeout,
+            } => PreparedCheck::Port {
+                host: host.unwrap_or_else(|| "127.0.0.1".to_owned()),
+                port,
+                mode,
                 timeout,
-                status,
+                own_process_groups: self
+                    .context
+                    .service_id()
+                    .map(|service_id| {
+                        self.context
+                            .query_system_state(|state| state.service_process_ids(&service_id))
+                    })
+                    .unwrap_or_default(),
+            },
+            Requirement::StateQuery { query } => PreparedCheck::StateQuery {
+                result_rx: self.context.enqueue_rhai(query.clone(), true),
+                query,
+            },
+            Requirement::Command {
+                command,
+                timeout,
+                exit_codes,
+            } => PreparedCheck::Command {
+                cmd: create_cmd(&command, Some(&self.workdir)),
+                entry: command,
+                timeout: command_timeout(
+                    timeout,
+                    self.timeout,
+                    self.start_time.elapsed(),
+                    self.last_failure.is_some(),
+                ),
+                exit_codes,
+            },
+            Requirement::Executable(requirement) => PreparedCheck::Executable {
+                requirement,
+                workdir: self.workdir.clone(),
+            },
+            Requirement::OutputPattern {
+                pattern,
+                failure_pattern,
+                service,
+                source,
             } => {
-                self.context.perform_concurrent_work(move || {
-                    let http_client = HttpClient::new();
-
-                    let result = http_client
-                        .request(
-                            match method {
-                                HttpMethod::GET => Method::GET,
-                                HttpMethod::POST => Method::POST,
-                                HttpMethod::PUT => Method::PUT,
-                                HttpMethod::PATCH => Method::PATCH,
-                                HttpMethod::DELETE => Method::DELETE,
-                                HttpMethod::OPTIONS => Method::OPTIONS,
-                            },
-                            &url,
-                        )
-                        .timeout(timeout)
-                        .send();
-
-                    match result {
-                        Ok(response) if response.status().as_u16() == status => {
-                            WorkResult {
-                                successful: true,
-                                output: vec![
-                                    format!("Req OK: {method} {url} responded with status {status}")
-                                ]
-                            }
-                        }
-                        Ok(response) => {
-                            let response_status: u16 = response.status().as_u16();
-
-                            WorkResult {
+                let outcome = self.match_output_pattern(
+                    &pattern,
+                    failure_pattern.as_deref(),
+                    service,
+                    source,
+                );
+                PreparedCheck::Done(WorkResult {
+                    successful: matches!(outcome, OutputPatternOutcome::Matched(_)),
+                    output: vec![outcome.message()],
+                })
+            }
+            Requirement::File {
+                paths,
+                newer_than,
+                newer_than_block_start,
+            } => {
+                let not_before = if newer_than_block_start {
+                    match self.block_start_time() {
+                        Some(start_time) => Some(start_time),
+                        None => {
+                            return PreparedCheck::Done(WorkResult {
                                 successful: false,
                                 output: vec![
-                                    format!("Req fail: {method} {url} responded with status {response_status} != {status}")
-                                ]
-                            }
-                        }
-                        Err(error) => {
-                            WorkResult {
-                                successful: false,
-                                output: vec![format_reqwest_error(&error)]
-                            }
+                                    "Req fail: newer_than_block_start is only supported in blocks"
+                                        .to_owned(),
+                                ],
+                            });
                         }
                     }
-                });
-            }
-            Requirement::Port { port, host } => {
-                let host = match host {
-                    Some(host) => host.clone(),
-                    None => "127.0.0.1".to_owned(),
+                } else {
+                    None
                 };
+                PreparedCheck::File {
+                    paths,
+                    workdir: self.workdir.clone(),
+                    newer_than,
+                    not_before,
+                }
+            }
+            Requirement::UnixSocket {
+                path,
+                http,
+                timeout,
+            } => PreparedCheck::UnixSocket {
+                path: Path::new(&self.workdir).join(path),
+                http,
+                timeout,
+            },
+            Requirement::Any {
+                requirements,
+                timeout,
+            } => PreparedCheck::Any {
+                branches: requirements
+                    .iter()
+                    .map(|requirement| self.prepare_check(requirement))
+                    .collect(),
+                timeout,
+            },
+            Requirement::All {
+                requirements,
+                timeout,
+            } => PreparedCheck::All {
+                branches: requirements
+                    .iter()
+                    .map(|requirement| self.prepare_check(requirement))
+                    .collect(),
+                timeout,
+            },
+            Requirement::Not {
+                requirement,
+                timeout,
+            } => PreparedCheck::Not {
+                branch: Box::new(self.prepare_check(&requirement)),
+                timeout,
+            },
+        }
+    }
 
-                self.context.perform_concurrent_work(move || {
-                    let successful = TcpListener::bind(format!("{host}:{port}")).is_err();
+    /// Records the reason of a failed check, preferring the lines of the output that state the failure over any other
+    /// output, such as that of a command.
+    fn record_failure(&self, output: Vec<String>) {
+        let failure_lines: Vec<&str> = output
+            .iter()
+            .map(String::as_str)
+            .filter(|line| line.contains("Req fail"))
+            .collect();
+        let message = if failure_lines.is_empty() {
+            output.join("\n")
+        } else {
+            failure_lines.join("\n")
+        };
 
-                    WorkResult {
-                        successful,
-                        output: if successful {
-                            vec![format!("Req OK: successsfully bound to {host}:{port}")]
-                        } else {
-                            vec![format!("Req fail: could not bind to {host}:{port}")]
-                        },
-                    }
+        if !message.is_empty() {
+            self.context.record_check_failure(message);
+        }
+    }
+
+    /// Start time of the latest run of the block being checked.
+    fn block_start_time(&self) -> Option<SystemTime> {
+        let service_id = self.context.service_id()?;
+        let block_id = self.context.block_id()?;
+        self.context.query_system_state(|state| {
+            state
+                .get_service(&service_id)?
+                .get_block_start_time(&block_id, &Local::now())
+                .map(SystemTime::from)
+        })
+    }
+
+    fn check_output_pattern(
+        &self,
+        pattern: &str,
+        failure_pattern: Option<&str>,
+        service: Option<ServiceId>,
+        source: Option<String>,
+    ) -> RequirementCheckResult {
+        match self.match_output_pattern(pattern, failure_pattern, service, source) {
+            OutputPatternOutcome::Matched(message) => {
+                self.context.perform_concurrent_work(move || WorkResult {
+                    successful: true,
+                    output: vec![message],
                 });
+                RequirementCheckResult::Working
             }
-            Requirement::StateQuery { query } => {
-                let result_rx = self.context.enqueue_rhai(query.clone(), true);
-
-                self.context
-                    .perform_concurrent_work(move || match result_rx.recv() {
-                        Ok(Ok(value)) if value.is::<bool>() => WorkResult {
-                            successful: value.as_bool().unwrap(),
-                            output: vec![format!("Query '{query}' => {value}")],
-                        },
-                        Ok(Ok(value)) => WorkResult {
-                            successful: false,
-                            output: vec![format!(
-                                "Error: Query outputted non-boolean: '{query}' => {value}"
-                            )],
-                        },
-                        Ok(Err(error)) => WorkResult {
-                            successful: false,
-                            output: vec![format!("Error in Rhai query {query}: {error:?}")],
-                        },
-                        Err(error) => WorkResult {
-                            successful: false,
-                            output: vec![format!(
-                                "Error in receiving response from Rhai executor: {error:?}"
-                            )],
-                        },
-                    });
+            OutputPatternOutcome::Pending(message) => {
+                self.context.perform_concurrent_work(move || WorkResult {
+                    successful: false,
+                    output: vec![message],
+                });
+                RequirementCheckResult::Working
             }
-            Requirement::File { paths } => {
-                let workdir = self.workdir.clone();
-
-                self.context.perform_concurrent_work(move || {
-                    let mut output = Vec::new();
-                    let mut success = true;
+            OutputPatternOutcome::FailureMatched(message)
+            | OutputPatternOutcome::Invalid(message) => {
+                self.context.record_check_failure(message.clone());
+                self.context.add_system_output(message);
+                RequirementCheckResult::Failed
+            }
+        }
+    }
 
-                    for path in paths {
-                        // Resolve real path: if not absolute, join with workdir
-                        let pattern_path = Path::new(&path);
-                        let full_pattern = if pattern_path.is_absolute() {
-                            pattern_path.to_path_buf()
-                        } else {
-                            Path::new(&workdir).join(pattern_path)
-                        };
+    fn match_output_pattern(
+        &self,
+        pattern: &str,
+        failure_pattern: Option<&str>,
+        service: Option<ServiceId>,
+        source: Option<String>,
+    ) -> OutputPatternOutcome {
+        let Some(source_name) =
+            source.or_else(|| self.context.block_id().map(|id| id.inner().to_owned()))
+        else {
+            return OutputPatternOutcome::Invalid(
+                "Req fail: output requirements outside of blocks need a source".to_owned(),
+            );
+        };
+        let patterns = Regex::new(pattern)
+            .and_then(|regex| Ok((regex, failure_pattern.map(Regex::new).transpose()?)));
+        let (regex, failure_regex) = match patterns {
+            Ok(patterns) => patterns,
+            Err(error) => {
+                return OutputPatternOutcome::Invalid(format!(
+                    "Req fail: invalid output pattern: {error}"
+                ));
+            }
+        };
+        let key = OutputKey {
+            service_id: service.or_else(|| self.context.service_id()),
+            source_name,
+            kind: OutputKind::ExtProcess,
+        };
+        let source_label = match &key.service_id {
+            Some(service_id) => format!("{service_id}/{}", key.source_name),
+            None => key.source_name.clone(),
+        };
 
-                        // Convert to string for glob; we don't canonicalize because the user wants glob expansion.
-                        let pattern_str = match full_pattern.to_str() {
-                            Some(s) => s.to_owned(),
-                            None => {
-                                output.push(format!("Could not convert path to string: {:?}. This indicates a bug in the runner", full_pattern));
-                                success = false;
-                                continue;
-                            }
-                        };
+        let check_start = Local::now()
+            - chrono::Duration::from_std(self.start_time.elapsed()).unwrap_or_default();
+        // The first line matching either pattern decides the outcome
+        let first_match = self.context.query_system_state(|state| {
+            let since = key
+                .service_id
+                .as_ref()
+                .and_then(|service_id| state.get_service(service_id))
+                .and_then(|service| {
+                    service.get_block_start_time(&BlockId::new(&key.source_name), &Local::now())
+                })
+                .unwrap_or(check_start);
 
-                        // Convert the create path into a glob
-                        success = match glob::glob(&pattern_str) {
-                            Ok(entries) => {
-                                let mut matched_any = false;
-                                for entry in entries {
-                                    match entry {
-                                        Ok(path) => {
-                                            if path.exists() {
-                                                matched_any = true;
-                                                break;
-                                            }
-                                        }
-                                        Err(e) => {
-                                            output.push(format!("Req fail: unexpected IO error when checking {}: {}", pattern_str, e));
-                                        }
-                                    }
-                                }
-                                if matched_any {
-                                    output.push(format!("Req OK: path {} exists", pattern_str));
-                                } else {
-                                    output.push(format!("Req fail: no file/dir found with '{}'", pattern_str));
-                                }
+            let lines = state.output_store.outputs.get(&key)?;
+            first_output_match(lines, since, &regex, failure_regex.as_ref())
+        });
 
-                                matched_any
-                            }
-                            Err(_) => {
-                                output.push(format!("Req fail: invalid glob pattern '{}'", pattern_str));
-                                false
-                            }
-                        } && success
-                    }
-
-                    WorkResult {
-                        successful: success,
-                        output
-                    }
-                });
+        match first_match {
+            Some((true, line)) => {
+                OutputPatternOutcome::Matched(format!("Req OK: {source_label} logged '{line}'"))
             }
-        };
+            Some((false, line)) => OutputPatternOutcome::FailureMatched(format!(
+                "Req fail: {source_label} logged '{line}', which matches the failure pattern"
+            )),
+            None => OutputPatternOutcome::Pending(format!(
+                "Req fail: {source_label} has not logged a line matching '{pattern}' yet"
+            )),
+        }
     }
 }
 
-/// Turns a `reqwest::Error` into a clean, human-readable string.
-pub fn format_reqwest_error(err: &reqwest::Error) -> String {
-    if err.is_connect() {
-        return format!("Connection error: {}", err);
-    }
-    if err.is_timeout() {
-        return "Request timed out.".to_string();
-    }
-    if err.is_request() {
-        return format!("Request build error: {}", err);
+enum OutputPatternOutcome {
+    Matched(String),
+    /// A line matching the failure pattern was logged first
+    FailureMatched(String),
+    Pending(String),
+    Invalid(String),
+}
+impl OutputPatternOutcome {
+    fn message(self) -> String {
+        match self {
+            OutputPatternOutcome::Matched(message)
+            | OutputPatternOutcome::FailureMatched(message)
+            | OutputPatternOutcome::Pending(message)
+            | OutputPatternOutcome::Invalid(message) => message,
+        }
     }
-    if err.is_body() {
-        return format!("Body error: {}", err);
+}
+
+/// The first occurrence at or after `since` among `lines` that matches either pattern, along with whether it matched
+/// the success pattern. Repeats collapsed into a line are checked by their own timestamps, so a line first received
+/// before `since` still matches if it was repeated afterwards.
+fn first_output_match(
+    lines: &VecDeque<OutputLine>,
+    since: DateTime<Local>,
+    regex: &Regex,
+    failure_regex: Option<&Regex>,
+) -> Option<(bool, String)> {
+    lines
+        .iter()
+        .skip_while(|line| line.latest_timestamp() < since)
+        .flat_map(|line| line.occurrences_since(since))
+        .find_map(|(_, value)| {
+            if failure_regex.is_some_and(|regex| regex.is_match(value)) {
+                Some((false, value.to_owned()))
+            } else if regex.is_match(value) {
+                Some((true, value.to_owned()))
+            } else {
+                None
+            }
+        })
+}
+
+/// Timeout of a command requirement. Retries must not outlive the check, so they are killed once the overall check
+/// timeout is reached. The first attempt always gets the full timeout of the command, as do all attempts if the check
+/// has no overall timeout, which is the case if it is zero.
+fn command_timeout(
+    timeout: Duration,
+    check_timeout: Option<Duration>,
+    elapsed: Duration,
+    is_retry: bool,
+) -> Duration {
+    match check_timeout {
+        Some(check_timeout) if is_retry && !check_timeout.is_zero() => {
+            timeout.min(check_timeout.saturating_sub(elapsed))
+        }
+        _ => timeout,
     }
-    if err.is_decode() {
-        return format!("Response decoding error: {}", err);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{command_timeout, first_output_match};
+    use crate::config::{CollapseRepeats, OutputRetention, ServiceId};
+    use crate::models::{LineDetails, OutputKey, OutputKind, OutputStore};
+    use chrono::Local;
+    use regex::Regex;
+    use std::time::Duration;
+
+    #[test]
+    fn test_command_timeout() {
+        let timeout = Duration::from_secs(10);
+
+        // Health checks without a configured timeout have a zero timeout
+        assert_eq!(
+            command_timeout(timeout, Some(Duration::ZERO), Duration::from_secs(1), false),
+            timeout
+        );
+        assert_eq!(
+            command_timeout(timeout, Some(Duration::ZERO), Duration::from_secs(1), true),
+            timeout
+        );
+        assert_eq!(
+            command_timeout(timeout, None, Duration::from_secs(1), true),
+            timeout
+        );
+
+        // The first attempt is never cut short, retries are limited to the remaining time
+        assert_eq!(
+            command_timeout(
+                timeout,
+                Some(Duration::from_secs(5)),
+                Duration::from_secs(3),
+                false
+            ),
+            timeout
+        );
+        assert_eq!(
+            command_timeout(
+                timeout,
+                Some(Duration::from_secs(5)),
+                Duration::from_secs(3),
+                true
+            ),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            command_timeout(
+                timeout,
+                Some(Duration::from_secs(60)),
+                Duration::from_secs(3),
+                true
+            ),
+            timeout
+        );
     }
 
-    // Fall back to a generic error message
-    format!("Unexpected error: {}", err)
+    #[test]
+    fn test_first_output_match_with_collapsed_repeats() {
+        let key = OutputKey {
+            service_id: Some(ServiceId::new("service")),
+            source_name: "run".to_string(),
+            kind: OutputKind::ExtProcess,
+        };
+        let mut store = OutputStore::new();
+        let start = Local::now();
+        for (seconds, line) in [
+            (0, "Listening on port 8080"),
+            (1, "Listening on port 8081"),
+            (2, "Connection lost"),
+            (3, "Connection lost"),
+        ] {
+            store.add_output(
+                &key,
+                line.to_string(),
+                start + chrono::Duration::seconds(seconds),
+                LineDetails::default(),
+                &OutputRetention::default(),
+                CollapseRepeats::IgnoreNumbers,
+            );
+        }
+        // All lines were collapsed into the first two
+        let lines = &store.outputs[&key];
+        assert_eq!(lines.len(), 2);
+
+        let regex = Regex::new("Listening on port (\\d+)").unwrap();
+        let failure_regex = Regex::new("Connection lost").unwrap();
+        let since = |seconds| start + chrono::Duration::seconds(seconds);
+        assert_eq!(
+            first_output_match(lines, since(0), &regex, Some(&failure_regex)),
+            Some((true, "Listening on port 8080".to_string()))
+        );
+        // Lines first received before the step started still match by their repeats, using the repeated value
+        assert_eq!(
+            first_output_match(lines, since(1), &regex, Some(&failure_regex)),
+            Some((true, "Listening on port 8081".to_string()))
+        );
+        assert_eq!(
+            first_output_match(lines, since(3), &regex, Some(&failure_regex)),
+            Some((false, "Connection lost".to_string()))
+        );
+        assert_eq!(
+            first_output_match(lines, since(4), &regex, Some(&failure_regex)),
+            None
+        );
+    }
 }
@@ -54,6 +54,20 @@ impl ServiceBlockContext {
 
     pub fn update_status(&self, status: BlockStatus) {
         let mut state = self.system_state.write().unwrap();
+        let was_working = state
+            .get_service(&self.service_id)
+            .is_some_and(|service| {
+                matches!(
+                    service.get_block_status(&self.block_id),
+                    BlockStatus::Working { .. }
+                )
+            });
+        if !was_working && matches!(status, BlockStatus::Working { .. }) {
+            // Don't attach the location of a problem in the new run to one that was left over from the previous run
+            state
+                .pending_problems
+                .clear(&self.service_id, &self.block_id);
+        }
         state.update_service(&self.service_id, |service| {
             service.update_block_status(&self.block_id, status)
         });
@@ -118,6 +132,31 @@ impl ServiceBlockContext {
             .map(|operation| operation.status())
     }
 
+    pub fn get_concurrent_operation_output(&self, operation_type: OperationType) -> Vec<String> {
+        self.system_state
+            .read()
+            .unwrap()
+            .get_concurrent_operation(&ConcurrentOperationKey::Block {
+                service_id: self.service_id.clone(),
+                block_id: self.block_id.clone(),
+                operation_type,
+            })
+            .map(|operation| operation.output())
+            .unwrap_or_default()
+    }
+
+    pub fn get_concurrent_operation_exit_code(&self, operation_type: OperationType) -> Option<i32> {
+        self.system_state
+            .read()
+            .unwrap()
+            .get_concurrent_operation(&ConcurrentOperationKey::Block {
+                service_id: self.service_id.clone(),
+                block_id: self.block_id.clone(),
+                operation_type,
+            })
+            .and_then(|operation| operation.exit_code())
+    }
+
     pub fn stop_concurrent_operation(&self, operation_type: OperationType) {
         self.system_state
             .read()
@@ -291,6 +330,16 @@ impl WorkContext for BlockWorkContext<'_> {
             .get_concurrent_operation_status(self.operation_type)
     }
 
+    fn get_concurrent_operation_output(&self) -> Vec<String> {
+        self.block_context
+            .get_concurrent_operation_output(self.operation_type)
+    }
+
+    fn get_concurrent_operation_exit_code(&self) -> Option<i32> {
+        self.block_context
+            .get_concurrent_operation_exit_code(self.operation_type)
+    }
+
     fn perform_concurrent_work<F>(&self, work: F)
     where
         F: FnOnce() -> WorkResult + Send + 'static,
@@ -328,4 +377,24 @@ impl WorkContext for BlockWorkContext<'_> {
     fn add_system_output(&self, output: String) {
         self.block_context.add_system_output(output);
     }
+
+    fn record_check_failure(&self, message: String) {
+        let block_id = self.block_id.clone();
+        self.update_service(|service| service.record_check_failure(&block_id, message));
+    }
+
+    fn service_id(&self) -> Option<ServiceId> {
+        Some(self.service_id.clone())
+    }
+
+    fn block_id(&self) -> Option<BlockId> {
+        Some(self.block_id.clone())
+    }
+
+    fn query_system_state<R, F>(&self, query: F) -> R
+    where
+        F: FnOnce(&SystemState) -> R,
+    {
+        query(&self.system_state.read().unwrap())
+    }
 }
@@ -1,3 +1,4 @@
+use crate::config::{BlockId, ServiceId};
 use crate::models::{OutputKey, OutputKind, Service, Task, TaskAction, TaskId, TaskStatus};
 use crate::runner::scripting::executor::{RhaiRequest, ScriptExecutor};
 use crate::runner::service_worker::work_context::WorkContext;
@@ -146,6 +147,27 @@ impl TaskContext {
             .map(|operation| operation.status())
     }
 
+    pub fn get_concurrent_operation_output(&self) -> Vec<String> {
+        self.system_state
+            .read()
+            .unwrap()
+            .get_concurrent_operation(&ConcurrentOperationKey::Task {
+                task_id: self.task_id,
+            })
+            .map(|operation| operation.output())
+            .unwrap_or_default()
+    }
+
+    pub fn get_concurrent_operation_exit_code(&self) -> Option<i32> {
+        self.system_state
+            .read()
+            .unwrap()
+            .get_concurrent_operation(&ConcurrentOperationKey::Task {
+                task_id: self.task_id,
+            })
+            .and_then(|operation| operation.exit_code())
+    }
+
     pub fn create_work_context(&self, silent: bool) -> TaskWorkContext {
         TaskWorkContext {
             task_context: self,
@@ -179,6 +201,14 @@ impl WorkContext for TaskWorkContext<'_> {
         self.task_context.get_concurrent_operation_status()
     }
 
+    fn get_concurrent_operation_output(&self) -> Vec<String> {
+        self.task_context.get_concurrent_operation_output()
+    }
+
+    fn get_concurrent_operation_exit_code(&self) -> Option<i32> {
+        self.task_context.get_concurrent_operation_exit_code()
+    }
+
     fn perform_concurrent_work<F>(&self, work: F)
     where
         F: FnOnce() -> WorkResult + Send + 'static,
@@ -237,4 +267,23 @@ impl WorkContext for TaskWorkContext<'_> {
             output,
         );
     }
+
+    fn record_check_failure(&self, _message: String) {
+        // Wait steps of tasks print the output of their checks, so there is nothing to keep track of
+    }
+
+    fn service_id(&self) -> Option<ServiceId> {
+        self.query_task(|task| task.service_id.clone())
+    }
+
+    fn block_id(&self) -> Option<BlockId> {
+        None
+    }
+
+    fn query_system_state<R, F>(&self, query: F) -> R
+    where
+        F: FnOnce(&SystemState) -> R,
+    {
+        query(&self.system_state.read().unwrap())
+    }
 }
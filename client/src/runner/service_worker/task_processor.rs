@@ -12,21 +12,28 @@ pub trait TaskProcessor {
 impl TaskProcessor for TaskContext {
     fn process_task(&self) {
         let task_status = self.get_status();
-        let (completed_steps, step_start_time, last_recoverable_failure) = match task_status {
-            TaskStatus::Running {
-                completed_steps,
-                step_start_time,
-                last_recoverable_failure,
-            } => (completed_steps, step_start_time, last_recoverable_failure),
-            TaskStatus::Failed | TaskStatus::Finished => {
-                error!(
-                    "System called process_task() but task {task_id}/{name} is not running",
-                    task_id = self.task_id,
-                    name = self.query_task(|task| task.name.clone())
-                );
-                return;
-            }
-        };
+        let (completed_steps, step_start_time, last_recoverable_failure, failed_attempts) =
+            match task_status {
+                TaskStatus::Running {
+                    completed_steps,
+                    step_start_time,
+                    last_recoverable_failure,
+                    failed_attempts,
+                } => (
+                    completed_steps,
+                    step_start_time,
+                    last_recoverable_failure,
+                    failed_attempts,
+                ),
+                TaskStatus::Failed | TaskStatus::Finished => {
+                    error!(
+                        "System called process_task() but task {task_id}/{name} is not running",
+                        task_id = self.task_id,
+                        name = self.query_task(|task| task.name.clone())
+                    );
+                    return;
+                }
+            };
 
         let work_seq: Vec<WorkSequenceEntry> =
             self.query_task(|task| task.steps.iter().map(|step| step.clone().into()).collect());
@@ -46,6 +53,7 @@ impl TaskProcessor for TaskContext {
             completed_count: completed_steps,
             entry_start_time: step_start_time,
             last_recoverable_failure,
+            failed_attempts,
             context: &self.create_work_context(false),
             workdir,
         }
@@ -57,6 +65,7 @@ impl TaskProcessor for TaskContext {
                     step_start_time: Instant::now(),
                     completed_steps: completed_steps + 1,
                     last_recoverable_failure: None,
+                    failed_attempts: 0,
                 });
             }
             WorkExecutionResult::RecoverableFailure => {
@@ -64,6 +73,7 @@ impl TaskProcessor for TaskContext {
                     step_start_time,
                     completed_steps,
                     last_recoverable_failure: Some(Instant::now()),
+                    failed_attempts: failed_attempts + 1,
                 });
             }
             WorkExecutionResult::AllOk => {
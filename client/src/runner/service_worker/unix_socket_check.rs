@@ -0,0 +1,326 @@
+use crate::config::UnixSocketHttp;
+use crate::runner::service_worker::WorkResult;
+use regex::Regex;
+use std::io;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Connects to a Unix domain socket, sending an HTTP request over it and checking the response if one is configured.
+pub fn check_unix_socket(
+    path: &Path,
+    http: Option<&UnixSocketHttp>,
+    timeout: Duration,
+) -> WorkResult {
+    let failure = |message: String| WorkResult {
+        successful: false,
+        output: vec![message],
+    };
+    let socket = path.display();
+
+    let mut stream = match UnixStream::connect(path) {
+        Ok(stream) => stream,
+        Err(error) => return failure(format!("Req fail: could not connect to {socket}: {error}")),
+    };
+    let Some(http) = http else {
+        return WorkResult {
+            successful: true,
+            output: vec![format!("Req OK: connected to {socket}")],
+        };
+    };
+
+    let UnixSocketHttp {
+        method,
+        path: request_path,
+        ..
+    } = http;
+    let response = match send_request(&mut stream, http, timeout) {
+        Ok(response) => response,
+        Err(error) => {
+            return failure(format!(
+                "Req fail: {method} {request_path} on {socket} failed: {error}"
+            ));
+        }
+    };
+
+    let Some(HttpResponse { status, body }) = parse_response(&response) else {
+        return failure(format!(
+            "Req fail: {method} {request_path} on {socket} did not get a complete HTTP response"
+        ));
+    };
+
+    if !http.status.matches(status) {
+        return failure(format!(
+            "Req fail: {method} {request_path} on {socket} responded with status {status}, expected {expected}",
+            expected = http.status
+        ));
+    }
+    if let Some(pattern) = &http.body_pattern {
+        match Regex::new(pattern) {
+            Ok(regex) if regex.is_match(&body) => {}
+            Ok(_) => {
+                return failure(format!(
+                    "Req fail: {method} {request_path} on {socket}: body does not match '{pattern}'"
+                ));
+            }
+            Err(error) => return failure(format!("Req fail: invalid body pattern: {error}")),
+        }
+    }
+
+    WorkResult {
+        successful: true,
+        output: vec![format!(
+            "Req OK: {method} {request_path} on {socket} responded with status {status}"
+        )],
+    }
+}
+
+/// Sends the request and reads the response until it is complete or the server closes the connection. The timeout
+/// applies to the exchange as a whole.
+fn send_request(
+    stream: &mut UnixStream,
+    http: &UnixSocketHttp,
+    timeout: Duration,
+) -> io::Result<Vec<u8>> {
+    let deadline = Instant::now() + timeout;
+    let remaining = || {
+        Some(deadline.saturating_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+    };
+    let timed_out = || {
+        io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("no response within {}", humantime::format_duration(timeout)),
+        )
+    };
+
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n",
+        http.method, http.path
+    );
+    for (name, value) in &http.headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    if let Some(body) = &http.body {
+        request.push_str(&format!("Content-Length: {}\r\n\r\n{body}", body.len()));
+    } else {
+        request.push_str("\r\n");
+    }
+    stream.set_write_timeout(Some(remaining().ok_or_else(timed_out)?))?;
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = Vec::new();
+    let mut buffer = [0; 8192];
+    // Servers ignoring the request to close the connection are only noticed by the deadline, by which time a response
+    // without a length has usually been received
+    let give_up = |response: Vec<u8>| {
+        if response.is_empty() {
+            Err(timed_out())
+        } else {
+            Ok(response)
+        }
+    };
+    loop {
+        let Some(remaining) = remaining() else {
+            return give_up(response);
+        };
+        stream.set_read_timeout(Some(remaining))?;
+        match stream.read(&mut buffer) {
+            Ok(0) => return Ok(response),
+            Ok(read) => {
+                response.extend_from_slice(&buffer[..read]);
+                if is_complete(&response) {
+                    return Ok(response);
+                }
+            }
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                return give_up(response);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct HttpResponse {
+    status: u16,
+    body: String,
+}
+
+/// Splits a response into its head and body, returning `None` if the head is incomplete.
+fn split_response(response: &[u8]) -> Option<(String, &[u8])> {
+    let end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")?;
+    Some((
+        String::from_utf8_lossy(&response[..end]).into_owned(),
+        &response[end + 4..],
+    ))
+}
+
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines().skip(1).find_map(|line| {
+        let (header, value) = line.split_once(':')?;
+        header
+            .trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim())
+    })
+}
+
+/// Whether the response has been received in full, as determined by its `Content-Length` or chunked encoding.
+/// Responses without either end when the connection is closed.
+fn is_complete(response: &[u8]) -> bool {
+    let Some((head, body)) = split_response(response) else {
+        return false;
+    };
+    if is_chunked(&head) {
+        decode_chunked(body).is_some()
+    } else if let Some(length) =
+        header(&head, "Content-Length").and_then(|length| length.parse().ok())
+    {
+        body.len() >= length
+    } else {
+        false
+    }
+}
+
+fn is_chunked(head: &str) -> bool {
+    header(head, "Transfer-Encoding")
+        .is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked"))
+}
+
+/// Parses the status code and body of a response. The status line looks like `HTTP/1.1 200 OK`. Chunked bodies are
+/// decoded, and `None` is returned if they're incomplete.
+fn parse_response(response: &[u8]) -> Option<HttpResponse> {
+    let (head, body) = split_response(response)?;
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("HTTP/"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse::<u16>().ok())?;
+    let body = if is_chunked(&head) {
+        decode_chunked(body)?
+    } else {
+        let length = header(&head, "Content-Length")
+            .and_then(|length| length.parse().ok())
+            .unwrap_or(body.len());
+        body[..length.min(body.len())].to_vec()
+    };
+
+    Some(HttpResponse {
+        status,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+/// Decodes a body with chunked transfer encoding, returning `None` until the final chunk has been received. Chunk
+/// extensions and trailers are ignored.
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|window| window == b"\r\n")?;
+        let size_line = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size_hex = size_line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size_hex, 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(decoded);
+        }
+        if body.len() < size + 2 {
+            return None;
+        }
+        decoded.extend_from_slice(&body[..size]);
+        body = &body[size + 2..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HttpResponse, check_unix_socket, is_complete, parse_response};
+    use crate::config::{HttpMethod, HttpStatusMatcher, UnixSocketHttp};
+    use std::collections::BTreeMap;
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn response(status: u16, body: &str) -> Option<HttpResponse> {
+        Some(HttpResponse {
+            status,
+            body: body.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let plain = b"HTTP/1.1 204 No Content\r\nServer: test\r\n\r\n";
+        assert_eq!(parse_response(plain), response(204, ""));
+        assert!(!is_complete(plain));
+
+        let sized = b"HTTP/1.0 200 OK\r\ncontent-length: 2\r\n\r\nOK";
+        assert_eq!(parse_response(sized), response(200, "OK"));
+        assert!(is_complete(sized));
+        assert!(!is_complete(&sized[..sized.len() - 1]));
+
+        let chunked = b"HTTP/1.1 503 Service Unavailable\r\nTransfer-Encoding: chunked\r\n\r\n\
+            6\r\n{\"ok\":\r\n6;ext=1\r\nfalse}\r\n0\r\n\r\n";
+        assert_eq!(parse_response(chunked), response(503, "{\"ok\":false}"));
+        assert!(is_complete(chunked));
+        let incomplete = &chunked[..chunked.len() - 7];
+        assert_eq!(parse_response(incomplete), None);
+        assert!(!is_complete(incomplete));
+
+        // Responses that aren't HTTP or lack a complete head
+        assert_eq!(parse_response(b"SSH-2.0-OpenSSH_9.6\r\n\r\n"), None);
+        assert_eq!(parse_response(b"HTTP/1.1 OK\r\n\r\n"), None);
+        assert_eq!(parse_response(b"HTTP/1.1 200 OK\r\n"), None);
+    }
+
+    #[test]
+    fn test_check_unix_socket() {
+        let path =
+            std::env::temp_dir().join(format!("unix-socket-check-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        // The server responds once it received the body, but keeps the connection open until the client closes it
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\nping") {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\npong")
+                .unwrap();
+            let _ = stream.read(&mut buffer);
+        });
+
+        let http = UnixSocketHttp {
+            path: "/ping".to_string(),
+            method: HttpMethod::POST,
+            status: HttpStatusMatcher::default(),
+            headers: BTreeMap::new(),
+            body: Some("ping".to_string()),
+            body_pattern: Some("^pong$".to_string()),
+        };
+        // The response is complete once its content length has been received, long before the timeout
+        let start = Instant::now();
+        let result = check_unix_socket(&path, Some(&http), Duration::from_secs(10));
+        assert!(result.successful, "{:?}", result.output);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        server.join().unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,4 +1,6 @@
+use crate::config::{BlockId, ServiceId};
 use crate::runner::service_worker::{ConcurrentOperationStatus, WorkResult};
+use crate::system_state::SystemState;
 use rhai::plugin::RhaiResult;
 use std::process::Child;
 use std::sync::mpsc::Receiver;
@@ -10,6 +12,10 @@ pub trait WorkContext {
 
     fn get_concurrent_operation_status(&self) -> Option<ConcurrentOperationStatus>;
 
+    fn get_concurrent_operation_output(&self) -> Vec<String>;
+
+    fn get_concurrent_operation_exit_code(&self) -> Option<i32>;
+
     fn perform_concurrent_work<F>(&self, work: F)
     where
         F: FnOnce() -> WorkResult + Send + 'static;
@@ -19,4 +25,17 @@ pub trait WorkContext {
     fn enqueue_rhai(&self, script: String, with_fn: bool) -> Receiver<RhaiResult>;
 
     fn add_system_output(&self, output: String);
+
+    /// Remembers why the latest requirement check failed, so that it can be displayed while the check is retried.
+    fn record_check_failure(&self, message: String);
+
+    /// Service the work belongs to, if any.
+    fn service_id(&self) -> Option<ServiceId>;
+
+    /// Block the work belongs to, unless it is part of a task.
+    fn block_id(&self) -> Option<BlockId>;
+
+    fn query_system_state<R, F>(&self, query: F) -> R
+    where
+        F: FnOnce(&SystemState) -> R;
 }
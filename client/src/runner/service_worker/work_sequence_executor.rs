@@ -1,4 +1,4 @@
-use crate::config::{ExecutableEntry, Requirement, TaskStep};
+use crate::config::{Requirement, SequenceCommand, TaskStep};
 use crate::runner::service_worker::create_cmd::create_cmd;
 use crate::runner::service_worker::requirement_checker::{
     RequirementCheckResult, RequirementChecker,
@@ -21,6 +21,8 @@ pub struct WorkSequenceExecutor<'a, W: WorkContext> {
     pub completed_count: usize,
     pub entry_start_time: Instant,
     pub last_recoverable_failure: Option<Instant>,
+    /// Number of failed attempts of the current entry, for entries that are retried
+    pub failed_attempts: usize,
     pub context: &'a W,
     pub workdir: String,
 }
@@ -30,7 +32,7 @@ impl<'a, W: WorkContext> WorkSequenceExecutor<'a, W> {
 
         match next_entry {
             None => WorkExecutionResult::AllOk,
-            Some(WorkSequenceEntry::ExecutableEntry(entry)) => self.handle_executable_entry(entry),
+            Some(WorkSequenceEntry::Command(step)) => self.handle_command(step),
             Some(WorkSequenceEntry::RhaiScript(script)) => self.handle_rhai_script(script.clone()),
             Some(WorkSequenceEntry::WaitRequirement {
                 timeout,
@@ -39,11 +41,32 @@ impl<'a, W: WorkContext> WorkSequenceExecutor<'a, W> {
         }
     }
 
-    fn handle_executable_entry(&self, entry: &ExecutableEntry) -> WorkExecutionResult {
+    fn handle_command(&self, step: &SequenceCommand) -> WorkExecutionResult {
+        let entry = &step.command;
+        let attempt = self.failed_attempts + 1;
+        let attempt_label = if step.attempts() > 1 {
+            format!(
+                " (attempt {attempt}/{attempts})",
+                attempts = step.attempts()
+            )
+        } else {
+            String::new()
+        };
+        // A retry is started as soon as the delay after the previous attempt has passed
+        let attempt_start_time = self
+            .last_recoverable_failure
+            .map(|failure| failure + step.retry_delay)
+            .unwrap_or(self.entry_start_time);
+        let timed_out = step
+            .timeout
+            .is_some_and(|timeout| attempt_start_time.elapsed() >= timeout);
+
         match self.context.get_concurrent_operation_status() {
+            None if Instant::now() < attempt_start_time => WorkExecutionResult::Working,
             None => match create_cmd(entry, Some(self.workdir.clone())) {
                 Ok(mut command) => {
-                    self.context.add_system_output(format!("Exec: {entry}"));
+                    self.context
+                        .add_system_output(format!("Exec{attempt_label}: {entry}"));
 
                     match command.spawn() {
                         Ok(process_handle) => {
@@ -65,14 +88,46 @@ impl<'a, W: WorkContext> WorkSequenceExecutor<'a, W> {
                     WorkExecutionResult::Failed
                 }
             },
-            Some(ConcurrentOperationStatus::Running) => WorkExecutionResult::Working,
-            Some(ConcurrentOperationStatus::Ok) => {
-                self.context.clear_concurrent_operation();
-                WorkExecutionResult::EntryOk
+            Some(ConcurrentOperationStatus::Running) => {
+                if timed_out {
+                    // The process is killed asynchronously, the attempt fails once it has stopped
+                    self.context.stop_concurrent_operation();
+                }
+                WorkExecutionResult::Working
             }
-            Some(ConcurrentOperationStatus::Failed) => {
+            Some(ConcurrentOperationStatus::Ok | ConcurrentOperationStatus::Failed) => {
+                let exit_code = self.context.get_concurrent_operation_exit_code();
                 self.context.clear_concurrent_operation();
-                WorkExecutionResult::Failed
+
+                let failure = match exit_code {
+                    // Commands are only polled periodically, so one that succeeded may not have been noticed before its timeout
+                    Some(code) if step.exit_codes.contains(&code) => {
+                        return WorkExecutionResult::EntryOk;
+                    }
+                    _ if timed_out => format!(
+                        "timed out after {}",
+                        humantime::format_duration(step.timeout.unwrap_or_default())
+                    ),
+                    Some(code) => format!("exited with code {code}"),
+                    None => "was terminated by a signal".to_string(),
+                };
+
+                if attempt < step.attempts() {
+                    self.context.add_system_output(format!(
+                        "Command failed{attempt_label}: {failure}, retrying in {}",
+                        humantime::format_duration(step.retry_delay)
+                    ));
+                    WorkExecutionResult::RecoverableFailure
+                } else if step.allow_failure {
+                    self.context.add_system_output(format!(
+                        "Command failed{attempt_label}: {failure}, continuing as failure is allowed"
+                    ));
+                    WorkExecutionResult::EntryOk
+                } else {
+                    self.context
+                        .add_system_output(format!("Command failed{attempt_label}: {failure}"));
+                    WorkExecutionResult::Failed
+                }
             }
         }
     }
@@ -135,29 +190,31 @@ impl<'a, W: WorkContext> WorkSequenceExecutor<'a, W> {
                 WorkExecutionResult::EntryOk
             }
             RequirementCheckResult::CurrentCheckFailed => WorkExecutionResult::RecoverableFailure,
-            RequirementCheckResult::Timeout => WorkExecutionResult::Failed,
+            RequirementCheckResult::Timeout | RequirementCheckResult::Failed => {
+                WorkExecutionResult::Failed
+            }
             RequirementCheckResult::Working => WorkExecutionResult::Working,
         }
     }
 }
 
 pub enum WorkSequenceEntry {
-    ExecutableEntry(ExecutableEntry),
+    Command(SequenceCommand),
     RhaiScript(String),
     WaitRequirement {
         timeout: Duration,
         requirement: Requirement,
     },
 }
-impl From<ExecutableEntry> for WorkSequenceEntry {
-    fn from(val: ExecutableEntry) -> Self {
-        WorkSequenceEntry::ExecutableEntry(val)
+impl From<SequenceCommand> for WorkSequenceEntry {
+    fn from(val: SequenceCommand) -> Self {
+        WorkSequenceEntry::Command(val)
     }
 }
 impl From<TaskStep> for WorkSequenceEntry {
     fn from(val: TaskStep) -> Self {
         match val {
-            TaskStep::Command { command } => WorkSequenceEntry::ExecutableEntry(command),
+            TaskStep::Command(command) => WorkSequenceEntry::Command(command),
             TaskStep::Action { action } => WorkSequenceEntry::RhaiScript(action),
             TaskStep::Wait {
                 timeout,
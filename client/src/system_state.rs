@@ -1,18 +1,42 @@
 use crate::config::{
-    AutomationDefinitionId, Block, BlockId, Config, ServiceId, TaskDefinition, TaskDefinitionId,
+    AutomationDefinitionId, Block, BlockId, Config, LogLevel, OutputRetention, ServiceId,
+    TaskDefinition, TaskDefinitionId,
 };
-use crate::models::{Automation, GetBlock, OutputKey, OutputStore, Profile, Service, Task, TaskId};
+use crate::models::{
+    Automation, ClassifiedLine, GetBlock, OutputClassifier, OutputKey, OutputKind, OutputStore,
+    PendingProblems, Preflight, Problem, Profile, Service, Task, TaskId,
+};
+use crate::runner::log_sink_writer::LogSinkLine;
+use crate::runner::output_collector::ReceivedOutput;
 use crate::runner::service_worker::ConcurrentOperationHandle;
-use std::collections::HashMap;
+use chrono::{DateTime, Local};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::mpsc::Sender;
 use std::thread::JoinHandle;
 
+const MAX_PROBLEMS_PER_SERVICE: usize = 1000;
+
 pub struct SystemState {
     pub current_profile: Option<Profile>,
+    /// Checks of the profile being activated, set until the profile is activated or the activation is cancelled
+    pub preflight: Option<Preflight>,
     pub output_store: OutputStore,
+    /// Shared with the threads reading the output of processes, which classify their lines before adding them
+    pub output_classifier: Arc<OutputClassifier>,
+    pub pending_problems: PendingProblems,
+    /// Output sources hidden from the output pane
+    pub disabled_outputs: HashSet<OutputKey>,
     pub config: Config,
     pub resolved_data_dir: String,
     pub should_exit: bool,
     pub active_threads: Vec<(String, JoinHandle<()>)>,
+    /// Channel for persisting output into log files. Only set if any service has a log file configured.
+    pub log_sink_tx: Option<Sender<LogSinkLine>>,
+    /// Editor command to run, picked up by the main loop as the terminal has to be suspended meanwhile
+    pub editor_request: Option<Vec<String>>,
+    /// Channel for adding the output of processes in batches. Only set while the output collector is running.
+    pub output_tx: Option<Sender<ReceivedOutput>>,
     concurrent_operations: HashMap<ConcurrentOperationKey, ConcurrentOperationHandle>,
 }
 
@@ -41,15 +65,42 @@ impl SystemState {
         SystemState {
             should_exit: false,
             current_profile: None,
+            preflight: None,
             output_store: OutputStore::new(),
+            output_classifier: Arc::new(OutputClassifier::new(&config)),
+            pending_problems: PendingProblems::default(),
+            disabled_outputs: HashSet::new(),
             active_threads: Vec::new(),
+            log_sink_tx: None,
+            output_tx: None,
+            editor_request: None,
             concurrent_operations: HashMap::new(),
             config,
             resolved_data_dir,
         }
     }
 
+    /// Activates the profile once the executables it requires have been checked. Profiles without such requirements
+    /// are activated right away.
+    pub fn start_preflight(&mut self, definition_id: &str) {
+        let preflight = Preflight::new(
+            self.config
+                .profiles
+                .iter()
+                .find(|def| def.id == definition_id)
+                .unwrap_or_else(|| panic!("No definition found with id {definition_id}")),
+            &self.config.services,
+        );
+
+        if preflight.checks.is_empty() {
+            self.select_profile(definition_id);
+        } else {
+            self.preflight = Some(preflight);
+        }
+    }
+
     pub fn select_profile(&mut self, definition_id: &str) {
+        self.preflight = None;
         self.current_profile = Some(Profile::new(
             self.config
                 .profiles
@@ -74,6 +125,22 @@ impl SystemState {
         self.concurrent_operations.get(key)
     }
 
+    /// Ids of the processes currently run by the blocks and tasks of a service. Each of them leads its own process
+    /// group.
+    pub fn service_process_ids(&self, service_id: &ServiceId) -> Vec<u32> {
+        self.concurrent_operations
+            .values()
+            .filter_map(|operation| match operation {
+                ConcurrentOperationHandle::Process(wrapper)
+                    if wrapper.service_id.as_ref() == Some(service_id) =>
+                {
+                    Some(wrapper.handle.lock().unwrap().id())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn has_block_operations(&self, service_id: &ServiceId, block_id: &BlockId) -> bool {
         [OperationType::Check, OperationType::Work]
             .iter()
@@ -256,7 +323,113 @@ impl SystemState {
         }
     }
 
+    /// Resolves the retention limits of an output source from the global settings and the overrides of its service.
+    pub fn output_retention(&self, key: &OutputKey) -> OutputRetention {
+        let global = &self.config.settings.output.retention;
+        key.service_id
+            .as_ref()
+            .and_then(|service_id| {
+                self.config
+                    .services
+                    .iter()
+                    .find(|service| &service.id == service_id)
+            })
+            .map(|service| service.output.retention.resolve(global, &key.source_name))
+            .unwrap_or(*global)
+    }
+
     pub fn add_output(&mut self, key: &OutputKey, line: String) {
-        self.output_store.add_output(key, line);
+        self.add_output_at(key, line, Local::now());
+    }
+
+    /// Adds a line of output that was received at the given time.
+    pub fn add_output_at(&mut self, key: &OutputKey, line: String, timestamp: DateTime<Local>) {
+        let classified = self.output_classifier.classify(key, &line);
+        self.add_classified_output(key, line, timestamp, classified);
+    }
+
+    /// Adds a line of output that was already classified, so that this doesn't have to be done while the system state
+    /// is locked.
+    pub fn add_classified_output(
+        &mut self,
+        key: &OutputKey,
+        line: String,
+        timestamp: DateTime<Local>,
+        classified: ClassifiedLine,
+    ) {
+        let level = classified.details.level;
+        let retention = self.output_retention(key);
+        let collapse = self.config.settings.output.collapse_repeats;
+        let stored = self.output_store.add_output(
+            key,
+            line,
+            timestamp,
+            classified.details,
+            &retention,
+            collapse,
+        );
+
+        if let Some(tx) = self.log_sink_tx.as_ref()
+            && let Some(service_id) = key.service_id.as_ref()
+            && self
+                .config
+                .services
+                .iter()
+                .any(|service| &service.id == service_id && service.output.log_file.is_some())
+        {
+            // Sending only fails once the writer has drained its queue and stopped during shutdown. Lines that arrive
+            // after that are intentionally not written to the log file.
+            let _ = tx.send(LogSinkLine {
+                service_id: service_id.clone(),
+                source_name: key.source_name.clone(),
+                kind: key.kind,
+                timestamp: stored.latest_timestamp(),
+                value: stored.latest_value().to_string(),
+            });
+        }
+
+        if key.kind == OutputKind::ExtProcess
+            && let Some(service_id) = key.service_id.as_ref()
+        {
+            let problems = self.pending_problems.process_line(
+                &self.output_classifier.problem_matchers,
+                service_id,
+                &BlockId::new(&key.source_name),
+                key,
+                classified.problems,
+                stored.index,
+            );
+            self.add_problems(service_id, problems);
+        }
+
+        if level == Some(LogLevel::Error)
+            && let Some(service_id) = key.service_id.as_ref()
+        {
+            self.update_service(service_id, |service| service.unseen_errors += 1);
+        }
+    }
+
+    /// Marks the end of an output, once the process writing it has closed its output streams.
+    pub fn end_output(&mut self, key: &OutputKey) {
+        if let Some(service_id) = key.service_id.as_ref()
+            && let Some(problem) = self.pending_problems.flush(key)
+        {
+            self.add_problems(service_id, vec![problem]);
+        }
+    }
+
+    fn add_problems(&mut self, service_id: &ServiceId, problems: Vec<Problem>) {
+        if problems.is_empty() {
+            return;
+        }
+        self.update_service(service_id, |service| {
+            service.problems.extend(problems);
+            // Keep the number of problems bounded if a tool reports excessively many of them
+            let excess = service
+                .problems
+                .len()
+                .saturating_sub(MAX_PROBLEMS_PER_SERVICE);
+            service.problems.drain(..excess);
+        });
     }
 }
@@ -47,12 +47,59 @@ impl RegisterKeybinds for ComponentRenderer {
         binds.common.focus_prev.bind_key(ATTR_KEY_FOCUS_PREV, self);
 
         binds.common.select.bind_key(ATTR_KEY_SELECT, self);
-        binds.common.select.bind_key(ATTR_KEY_CANCEL, self);
+        binds.common.cancel.bind_key(ATTR_KEY_CANCEL, self);
 
         binds
             .output
             .toggle_wrap
             .bind_key(ATTR_KEY_TOGGLE_WRAP, self);
+        binds
+            .output
+            .cycle_min_level
+            .bind_key(ATTR_KEY_CYCLE_MIN_LEVEL, self);
+        binds.output.search.bind_key(ATTR_KEY_SEARCH, self);
+        binds
+            .output
+            .search_next
+            .bind_key(ATTR_KEY_SEARCH_NEXT, self);
+        binds
+            .output
+            .search_prev
+            .bind_key(ATTR_KEY_SEARCH_PREV, self);
+        binds.output.filter.bind_key(ATTR_KEY_FILTER, self);
+        binds
+            .output
+            .toggle_source_picker
+            .bind_key(ATTR_KEY_TOGGLE_SOURCE_PICKER, self);
+        binds.output.export.bind_key(ATTR_KEY_EXPORT_OUTPUT, self);
+        binds
+            .output
+            .cycle_timestamps
+            .bind_key(ATTR_KEY_CYCLE_TIMESTAMPS, self);
+        binds
+            .output
+            .jump_to_time
+            .bind_key(ATTR_KEY_JUMP_TO_TIME, self);
+        binds
+            .output
+            .toggle_expand
+            .bind_key(ATTR_KEY_TOGGLE_EXPAND_LINE, self);
+        binds
+            .output
+            .toggle_problems
+            .bind_key(ATTR_KEY_TOGGLE_PROBLEMS, self);
+        binds
+            .output
+            .open_in_editor
+            .bind_key(ATTR_KEY_OPEN_IN_EDITOR, self);
+        binds
+            .output
+            .toggle_follow
+            .bind_key(ATTR_KEY_TOGGLE_FOLLOW, self);
+        binds
+            .output
+            .toggle_query_regex
+            .bind_key(ATTR_KEY_TOGGLE_QUERY_REGEX, self);
 
         binds
             .service
@@ -70,6 +117,10 @@ impl RegisterKeybinds for ComponentRenderer {
             .service
             .toggle_automation_all
             .bind_key(ATTR_KEY_TOGGLE_ALL_AUTOMATIONS, self);
+        binds
+            .service
+            .toggle_block_details
+            .bind_key(ATTR_KEY_TOGGLE_BLOCK_DETAILS, self);
 
         self.set_attr(
             ATTR_KEY_BLOCK_ACTIONS,
@@ -98,6 +149,27 @@ pub const ATTR_KEY_FOCUS_NEXT: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds
 pub const ATTR_KEY_FOCUS_PREV: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.common.focus_prev");
 
 pub const ATTR_KEY_TOGGLE_WRAP: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.text_area.toggle_wrap");
+pub const ATTR_KEY_CYCLE_MIN_LEVEL: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.output.cycle_min_level");
+pub const ATTR_KEY_SEARCH: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.output.search");
+pub const ATTR_KEY_SEARCH_NEXT: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.output.search_next");
+pub const ATTR_KEY_SEARCH_PREV: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.output.search_prev");
+pub const ATTR_KEY_FILTER: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.output.filter");
+pub const ATTR_KEY_TOGGLE_SOURCE_PICKER: AttrKey<Vec<KeyMatcher>> =
+    AttrKey::new("keybinds.output.toggle_source_picker");
+pub const ATTR_KEY_EXPORT_OUTPUT: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.output.export");
+pub const ATTR_KEY_CYCLE_TIMESTAMPS: AttrKey<Vec<KeyMatcher>> =
+    AttrKey::new("keybinds.output.cycle_timestamps");
+pub const ATTR_KEY_JUMP_TO_TIME: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.output.jump_to_time");
+pub const ATTR_KEY_TOGGLE_EXPAND_LINE: AttrKey<Vec<KeyMatcher>> =
+    AttrKey::new("keybinds.output.toggle_expand");
+pub const ATTR_KEY_TOGGLE_PROBLEMS: AttrKey<Vec<KeyMatcher>> =
+    AttrKey::new("keybinds.output.toggle_problems");
+pub const ATTR_KEY_OPEN_IN_EDITOR: AttrKey<Vec<KeyMatcher>> =
+    AttrKey::new("keybinds.output.open_in_editor");
+pub const ATTR_KEY_TOGGLE_FOLLOW: AttrKey<Vec<KeyMatcher>> =
+    AttrKey::new("keybinds.output.toggle_follow");
+pub const ATTR_KEY_TOGGLE_QUERY_REGEX: AttrKey<Vec<KeyMatcher>> =
+    AttrKey::new("keybinds.output.toggle_query_regex");
 
 pub const ATTR_KEY_TOGGLE_SELECTED_OUTPUT: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.services.toggle_selected_output");
 pub const ATTR_KEY_TOGGLE_ALL_OUTPUT: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.services.toggle_all_output");
@@ -105,5 +177,7 @@ pub const ATTR_KEY_TOGGLE_ALL_OUTPUT: AttrKey<Vec<KeyMatcher>> = AttrKey::new("k
 pub const ATTR_KEY_TOGGLE_SELECTED_AUTOMATIONS: AttrKey<Vec<KeyMatcher>> =
     AttrKey::new("keybinds.services.toggle_selected_automation");
 pub const ATTR_KEY_TOGGLE_ALL_AUTOMATIONS: AttrKey<Vec<KeyMatcher>> = AttrKey::new("keybinds.services.toggle_all_automation");
+pub const ATTR_KEY_TOGGLE_BLOCK_DETAILS: AttrKey<Vec<KeyMatcher>> =
+    AttrKey::new("keybinds.services.toggle_block_details");
 
 pub const ATTR_KEY_BLOCK_ACTIONS: AttrKey<Vec<ResolvedBlockActionBinding>> = AttrKey::new("keymappings.service_list.block_actions");
@@ -1,5 +1,6 @@
 use crate::SystemState;
 use crate::ui::inputs::ATTR_KEY_QUIT;
+use crate::ui::screens::preflight::PreflightScreen;
 use crate::ui::screens::select_profile::SelectProfileScreen;
 use crate::ui::screens::view_profile::ViewProfileScreen;
 use ui::component::Component;
@@ -19,11 +20,16 @@ impl<'a> Component for ViewRoot<'a> {
 
     fn render(self, context: &mut FrameContext) -> UIResult<Self::Output> {
         let has_profile = self.system_state.current_profile.is_some();
+        let has_preflight = self.system_state.preflight.is_some();
 
         if has_profile {
             context.render_component(RenderArgs::new(ViewProfileScreen {
                 system_state: self.system_state,
             }))?;
+        } else if has_preflight {
+            context.render_component(RenderArgs::new(PreflightScreen {
+                system_state: self.system_state,
+            }))?;
         } else {
             context.render_component(RenderArgs::new(SelectProfileScreen {
                 system_state: self.system_state,
@@ -1,2 +1,3 @@
+pub mod preflight;
 pub mod select_profile;
 pub mod view_profile;
@@ -0,0 +1,125 @@
+use crate::models::{PreflightCheck, PreflightStatus};
+use crate::system_state::SystemState;
+use crate::ui::theming::{
+    ATTR_COLOR_FOCUSED_ELEMENT, ATTR_COLOR_WORK_ACTIVE, ATTR_COLOR_WORK_ERROR,
+    ATTR_COLOR_WORK_PROCESSING,
+};
+use ratatui::style::Color;
+use ui::component::{
+    ATTR_KEY_CANCEL, ATTR_KEY_SELECT, Align, Cell, Component, Dir, Flow, FlowableArgs, Text,
+};
+use ui::input::KeyMatcherQueryable;
+use ui::{FrameContext, RenderArgs, UIResult};
+
+/// Lists the executables required by the profile being activated while they are checked. The profile is activated
+/// once all of them are found, otherwise the missing ones are listed until the activation is forced or cancelled.
+pub struct PreflightScreen<'a> {
+    pub system_state: &'a mut SystemState,
+}
+impl<'a> PreflightScreen<'a> {
+    fn label(check: &PreflightCheck) -> String {
+        check
+            .alternatives
+            .iter()
+            .map(|requirement| {
+                let mut label = requirement.executable.clone();
+                if let Some(version) = &requirement.version {
+                    label.push_str(&format!(" {version}"));
+                }
+                if let Some(pattern) = &requirement.version_pattern {
+                    label.push_str(&format!(" /{pattern}/"));
+                }
+                label
+            })
+            .collect::<Vec<_>>()
+            .join(" or ")
+    }
+}
+
+impl<'a> Component for PreflightScreen<'a> {
+    type Output = ();
+
+    fn render(self, context: &mut FrameContext) -> UIResult<Self::Output> {
+        let Some(preflight) = self.system_state.preflight.as_ref() else {
+            return Ok(());
+        };
+        let profile_id = preflight.profile_id.clone();
+
+        // Starting the profile anyway is only offered once all checks have finished
+        if preflight.is_finished()
+            && (preflight.is_successful()
+                || context
+                    .signals()
+                    .is_key_pressed(context.req_attr(ATTR_KEY_SELECT)?))
+        {
+            self.system_state.select_profile(&profile_id);
+            return Ok(());
+        }
+        if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_CANCEL)?)
+        {
+            self.system_state.preflight = None;
+            return Ok(());
+        }
+
+        let max_width = context.size().width * 2 / 3;
+        let max_height = context.size().height * 2 / 3;
+        let focused_color = *context.req_attr::<Color>(ATTR_COLOR_FOCUSED_ELEMENT)?;
+        let active_color = *context.req_attr::<Color>(ATTR_COLOR_WORK_ACTIVE)?;
+        let processing_color = *context.req_attr::<Color>(ATTR_COLOR_WORK_PROCESSING)?;
+        let error_color = *context.req_attr::<Color>(ATTR_COLOR_WORK_ERROR)?;
+
+        let mut lines: Vec<(String, Color)> = Vec::new();
+        for check in &preflight.checks {
+            let label = Self::label(check);
+            match &check.status {
+                PreflightStatus::Pending | PreflightStatus::Running => {
+                    lines.push((format!("...  {label}"), processing_color));
+                }
+                PreflightStatus::Ok(message) => {
+                    lines.push((
+                        format!("ok   {label}: {}", message.trim_start_matches("Req OK: ")),
+                        active_color,
+                    ));
+                }
+                PreflightStatus::Failed(messages) => {
+                    lines.push((
+                        format!("FAIL {label}, required by {}", check.required_by.join(", ")),
+                        error_color,
+                    ));
+                    lines.extend(
+                        messages
+                            .iter()
+                            .map(|message| (format!("     {message}"), error_color)),
+                    );
+                }
+            }
+        }
+        if preflight.is_finished() {
+            lines.push((String::new(), focused_color));
+            lines.push((
+                "Select to start the profile anyway, cancel to go back".to_string(),
+                focused_color,
+            ));
+        }
+
+        let mut flow = Flow::new().dir(Dir::UpDown);
+        for (line, color) in lines {
+            flow = flow.element(Text::new(line).fg(color), FlowableArgs { fill: false });
+        }
+        context.render_component(RenderArgs::new(
+            Cell::new(
+                Cell::new(flow)
+                    .border(focused_color, &format!("Checking tools of {profile_id}"))
+                    .bg(Color::Reset)
+                    .min_width(40)
+                    .max_width(max_width)
+                    .max_height(max_height),
+            )
+            .align(Align::Center),
+        ))?;
+
+        Ok(())
+    }
+}
@@ -45,7 +45,7 @@ impl<'a> Component for SelectProfileScreen<'a> {
             let profile_id = self.system_state.config.profiles[selection.selected_index]
                 .id
                 .clone();
-            self.system_state.select_profile(&profile_id);
+            self.system_state.start_preflight(&profile_id);
         }
 
         Ok(())
@@ -0,0 +1,262 @@
+use crate::config::{Block, Requirement};
+use crate::models::{BlockStatus, Service, WorkStep};
+use crate::system_state::SystemState;
+use crate::ui::theming::{
+    ATTR_COLOR_FOCUSED_ELEMENT, ATTR_COLOR_WORK_ACTIVE, ATTR_COLOR_WORK_ERROR,
+    ATTR_COLOR_WORK_IDLE, ATTR_COLOR_WORK_PROCESSING, ATTR_COLOR_WORK_WAITING_TO_PROCESS,
+};
+use ratatui::style::Color;
+use std::time::{Duration, Instant};
+use ui::component::{Align, Cell, Component, Dir, Flow, FlowableArgs, Text};
+use ui::{FrameContext, RenderArgs, UIResult};
+
+/// Popup describing what each block of a service is doing, including the progress of its requirement checks and the
+/// reason why the latest check failed.
+pub struct BlockDetails<'a> {
+    pub system_state: &'a SystemState,
+    pub service_index: usize,
+}
+impl<'a> BlockDetails<'a> {
+    /// Describes the requirement checks performed by the step of a working block, if any.
+    fn checks(block: &Block, step: &WorkStep) -> Option<CheckProgress> {
+        match step {
+            WorkStep::PrerequisiteCheck {
+                start_time,
+                checks_completed,
+                ..
+            } => Some(CheckProgress {
+                label: "Checking prerequisites",
+                requirements: block.prerequisites.clone(),
+                completed: *checks_completed,
+                start_time: *start_time,
+                timeout: None,
+            }),
+            WorkStep::PreWorkHealthCheck {
+                start_time,
+                checks_completed,
+            } => Some(CheckProgress {
+                label: "Checking health before work",
+                requirements: block.health.requirements.clone(),
+                completed: *checks_completed,
+                start_time: *start_time,
+                timeout: None,
+            }),
+            WorkStep::PostWorkHealthCheck {
+                start_time,
+                checks_completed,
+                ..
+            } => Some(CheckProgress {
+                label: "Checking health after work",
+                requirements: block.health.requirements.clone(),
+                completed: *checks_completed,
+                start_time: *start_time,
+                timeout: Some(block.health.timeout),
+            }),
+            _ => None,
+        }
+    }
+
+    fn block_lines(
+        &self,
+        service: &Service,
+        block: &Block,
+        colors: &Colors,
+    ) -> Vec<(String, Color)> {
+        let mut lines = Vec::new();
+        let name = block.id.inner();
+
+        let mut checks = None;
+        match service.get_block_status(&block.id) {
+            BlockStatus::Initial => lines.push((format!("{name}: not started"), colors.idle)),
+            BlockStatus::Disabled => lines.push((format!("{name}: disabled"), colors.idle)),
+            BlockStatus::Error => lines.push((format!("{name}: failed"), colors.error)),
+            BlockStatus::Ok { liveness, .. } => {
+                if liveness.unhealthy {
+                    lines.push((format!("{name}: unhealthy"), colors.error));
+                } else if liveness.is_degraded() {
+                    lines.push((
+                        format!(
+                            "{name}: ok, {} failed liveness rounds",
+                            liveness.consecutive_failures
+                        ),
+                        colors.waiting,
+                    ));
+                } else {
+                    lines.push((format!("{name}: ok"), colors.active));
+                }
+
+                if liveness.checking
+                    && let Some(config) = &block.health.liveness
+                {
+                    checks = Some(CheckProgress {
+                        label: "Checking liveness",
+                        requirements: config
+                            .requirements
+                            .clone()
+                            .unwrap_or_else(|| block.health.requirements.clone()),
+                        completed: liveness.checks_completed,
+                        start_time: liveness.round_time,
+                        timeout: None,
+                    });
+                }
+            }
+            BlockStatus::Working { step } => {
+                match &step {
+                    WorkStep::ResourceGroupCheck { .. } => lines.push((
+                        format!(
+                            "{name}: waiting for resource group {}",
+                            block.resource_group.as_deref().unwrap_or_default()
+                        ),
+                        colors.waiting,
+                    )),
+                    WorkStep::PrerequisiteCheck {
+                        last_failure: Some(_),
+                        ..
+                    } => lines.push((
+                        format!("{name}: waiting to retry prerequisites"),
+                        colors.waiting,
+                    )),
+                    WorkStep::PreWorkFingerprintCheck => {
+                        lines.push((format!("{name}: checking fingerprint"), colors.processing))
+                    }
+                    WorkStep::PerformWork {
+                        current_step_started,
+                        steps_completed,
+                        failed_attempts,
+                        ..
+                    } => lines.push((
+                        format!(
+                            "{name}: working on step {}{attempt}, for {}",
+                            steps_completed + 1,
+                            format_elapsed(current_step_started.elapsed()),
+                            attempt = if *failed_attempts > 0 {
+                                format!(", attempt {}", failed_attempts + 1)
+                            } else {
+                                String::new()
+                            }
+                        ),
+                        if *failed_attempts > 0 {
+                            colors.waiting
+                        } else {
+                            colors.processing
+                        },
+                    )),
+                    _ => lines.push((format!("{name}: working"), colors.processing)),
+                }
+                checks = Self::checks(block, &step);
+            }
+        }
+
+        if let Some(CheckProgress {
+            label,
+            requirements,
+            completed,
+            start_time,
+            timeout,
+        }) = checks
+        {
+            let elapsed = format_elapsed(start_time.elapsed());
+            lines.push((
+                format!(
+                    "  {label}: {completed}/{total} done, {elapsed}{timeout}",
+                    total = requirements.len(),
+                    // Health checks without a configured timeout have a zero timeout, meaning they never time out
+                    timeout = timeout
+                        .filter(|timeout| !timeout.is_zero())
+                        .map(|timeout| format!(" of {}", humantime::format_duration(timeout)))
+                        .unwrap_or_default()
+                ),
+                colors.processing,
+            ));
+            if let Some(requirement) = requirements.get(completed) {
+                lines.push((format!("  Current: {requirement}"), colors.processing));
+            }
+        }
+
+        if let Some(failure) = service.get_check_failure(&block.id) {
+            lines.push((
+                format!(
+                    "  Last failure, {} ago:",
+                    format_elapsed(failure.time.elapsed())
+                ),
+                colors.error,
+            ));
+            lines.extend(
+                failure
+                    .message
+                    .lines()
+                    .map(|line| (format!("    {}", line.trim()), colors.error)),
+            );
+        }
+
+        lines
+    }
+}
+
+impl<'a> Component for BlockDetails<'a> {
+    type Output = ();
+
+    fn render(self, context: &mut FrameContext) -> UIResult<Self::Output> {
+        let Some(service) = self
+            .system_state
+            .current_profile
+            .as_ref()
+            .and_then(|profile| profile.services.get(self.service_index))
+        else {
+            return Ok(());
+        };
+
+        let max_width = context.size().width * 2 / 3;
+        let max_height = context.size().height * 2 / 3;
+        let focused_color = *context.req_attr::<Color>(ATTR_COLOR_FOCUSED_ELEMENT)?;
+        let colors = Colors {
+            idle: *context.req_attr::<Color>(ATTR_COLOR_WORK_IDLE)?,
+            active: *context.req_attr::<Color>(ATTR_COLOR_WORK_ACTIVE)?,
+            processing: *context.req_attr::<Color>(ATTR_COLOR_WORK_PROCESSING)?,
+            waiting: *context.req_attr::<Color>(ATTR_COLOR_WORK_WAITING_TO_PROCESS)?,
+            error: *context.req_attr::<Color>(ATTR_COLOR_WORK_ERROR)?,
+        };
+
+        let mut flow = Flow::new().dir(Dir::UpDown);
+        for block in &service.definition.blocks {
+            for (line, color) in self.block_lines(service, block, &colors) {
+                flow = flow.element(Text::new(line).fg(color), FlowableArgs { fill: false });
+            }
+        }
+        context.render_component(RenderArgs::new(
+            Cell::new(
+                Cell::new(flow)
+                    .border(focused_color, service.definition.id.inner())
+                    .bg(Color::Reset)
+                    .min_width(40)
+                    .max_width(max_width)
+                    .max_height(max_height),
+            )
+            .align(Align::Center),
+        ))?;
+
+        Ok(())
+    }
+}
+
+struct CheckProgress {
+    label: &'static str,
+    requirements: Vec<Requirement>,
+    completed: usize,
+    start_time: Instant,
+    /// Time after which the checks fail, if they are retried until then. A zero timeout means they never time out.
+    timeout: Option<Duration>,
+}
+
+struct Colors {
+    idle: Color,
+    active: Color,
+    processing: Color,
+    waiting: Color,
+    error: Color,
+}
+
+/// Formats a duration to whole seconds, as the popup is redrawn too often for finer precision to be readable.
+fn format_elapsed(duration: Duration) -> humantime::FormattedDuration {
+    humantime::format_duration(Duration::from_secs(duration.as_secs()))
+}
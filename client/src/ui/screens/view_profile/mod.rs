@@ -1,52 +1,153 @@
+mod block_details;
 mod output_display;
+mod output_filter;
 mod output_pane;
+mod output_search;
+mod problems_pane;
+mod query_input;
 mod service_list;
+mod source_picker;
+mod timestamps;
 
+use crate::config::LogLevel;
 use crate::system_state::SystemState;
-use crate::ui::inputs::{ATTR_KEY_FOCUS_NEXT, ATTR_KEY_FOCUS_PREV, ATTR_KEY_TOGGLE_WRAP};
+use crate::ui::inputs::{
+    ATTR_KEY_CYCLE_MIN_LEVEL, ATTR_KEY_CYCLE_TIMESTAMPS, ATTR_KEY_FOCUS_NEXT, ATTR_KEY_FOCUS_PREV,
+    ATTR_KEY_TOGGLE_BLOCK_DETAILS, ATTR_KEY_TOGGLE_PROBLEMS, ATTR_KEY_TOGGLE_SOURCE_PICKER,
+    ATTR_KEY_TOGGLE_WRAP,
+};
+use crate::ui::screens::view_profile::problems_pane::ProblemsPane;
+use crate::ui::screens::view_profile::timestamps::TimestampMode;
 use crate::ui::theming::{ATTR_COLOR_FOCUSED_ELEMENT, ATTR_COLOR_UNFOCUSED_ELEMENT};
 use ratatui::layout::Size;
 use ratatui::prelude::Color;
-use ui::component::{Align, Cell, StatefulComponent, WithZeroMeasurement};
+use ui::component::{ATTR_KEY_CANCEL, Align, Cell, StatefulComponent, Text, WithZeroMeasurement};
 use ui::input::KeyMatcherQueryable;
 use ui::{FrameContext, RenderArgs, SignalHandling, UIError, UIResult};
 
 pub struct ViewProfileScreen<'a> {
     pub system_state: &'a mut SystemState,
 }
-impl<'a> StatefulComponent for ViewProfileScreen<'a> {
-    type State = ViewProfileScreenState;
-    type Output = ();
+impl<'a> ViewProfileScreen<'a> {
+    fn process_inputs(
+        &self,
+        context: &mut FrameContext,
+        state: &mut ViewProfileScreenState,
+    ) -> UIResult<()> {
+        // The source picker captures all other inputs while it's open
+        if state.show_source_picker {
+            if context
+                .signals()
+                .is_key_pressed(context.req_attr(ATTR_KEY_TOGGLE_SOURCE_PICKER)?)
+                || context
+                    .signals()
+                    .is_key_pressed(context.req_attr(ATTR_KEY_CANCEL)?)
+            {
+                state.show_source_picker = false;
+            }
+            return Ok(());
+        } else if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_TOGGLE_SOURCE_PICKER)?)
+        {
+            state.show_source_picker = true;
+            return Ok(());
+        }
 
-    fn state_id(&self) -> &str {
-        "view-profile-screen"
-    }
+        // The block details popup only displays information, so closing it is the only input it handles
+        if state.show_block_details {
+            if context
+                .signals()
+                .is_key_pressed(context.req_attr(ATTR_KEY_TOGGLE_BLOCK_DETAILS)?)
+                || context
+                    .signals()
+                    .is_key_pressed(context.req_attr(ATTR_KEY_CANCEL)?)
+            {
+                state.show_block_details = false;
+            }
+            return Ok(());
+        } else if state.focused_pane == FocusedPane::ServiceList
+            && context
+                .signals()
+                .is_key_pressed(context.req_attr(ATTR_KEY_TOGGLE_BLOCK_DETAILS)?)
+        {
+            state.show_block_details = true;
+            return Ok(());
+        }
 
-    fn render(self, context: &mut FrameContext, state: &mut Self::State) -> UIResult<Self::Output> {
+        if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_TOGGLE_PROBLEMS)?)
+        {
+            state.show_problems = !state.show_problems;
+            state.focused_pane = if state.show_problems {
+                FocusedPane::Problems
+            } else if state.focused_pane == FocusedPane::Problems {
+                FocusedPane::OutputArea
+            } else {
+                state.focused_pane
+            };
+        }
         if context
             .signals()
             .is_key_pressed(context.req_attr(ATTR_KEY_TOGGLE_WRAP)?)
         {
             state.wrap_output = !state.wrap_output;
         }
+        if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_CYCLE_TIMESTAMPS)?)
+        {
+            state.timestamps = state.timestamps.next();
+        }
+        if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_CYCLE_MIN_LEVEL)?)
+        {
+            state.min_level = match state.min_level {
+                None => Some(LogLevel::Info),
+                Some(LogLevel::Debug | LogLevel::Info) => Some(LogLevel::Warn),
+                Some(LogLevel::Warn) => Some(LogLevel::Error),
+                Some(LogLevel::Error) => None,
+            };
+        }
         if context
             .signals()
             .is_key_pressed(context.req_attr(ATTR_KEY_FOCUS_NEXT)?)
         {
             state.focused_pane = match state.focused_pane {
                 FocusedPane::ServiceList => FocusedPane::OutputArea,
-                FocusedPane::OutputArea => FocusedPane::ServiceList,
+                FocusedPane::OutputArea if state.show_problems => FocusedPane::Problems,
+                FocusedPane::OutputArea | FocusedPane::Problems => FocusedPane::ServiceList,
             };
         } else if context
             .signals()
             .is_key_pressed(context.req_attr(ATTR_KEY_FOCUS_PREV)?)
         {
             state.focused_pane = match state.focused_pane {
-                FocusedPane::ServiceList => FocusedPane::OutputArea,
+                FocusedPane::ServiceList if state.show_problems => FocusedPane::Problems,
+                FocusedPane::ServiceList | FocusedPane::Problems => FocusedPane::OutputArea,
                 FocusedPane::OutputArea => FocusedPane::ServiceList,
             };
         }
 
+        Ok(())
+    }
+}
+impl<'a> StatefulComponent for ViewProfileScreen<'a> {
+    type State = ViewProfileScreenState;
+    type Output = ();
+
+    fn state_id(&self) -> &str {
+        "view-profile-screen"
+    }
+
+    fn render(self, context: &mut FrameContext, state: &mut Self::State) -> UIResult<Self::Output> {
+        // While the output pane captures text input, key presses must not trigger any of the screen's actions
+        if !state.output_captures_input {
+            self.process_inputs(context, state)?;
+        }
+
         let focused_color = *context.req_attr::<Color>(ATTR_COLOR_FOCUSED_ELEMENT)?;
         let unfocused_color = *context.req_attr::<Color>(ATTR_COLOR_UNFOCUSED_ELEMENT)?;
         let self_size = context.size();
@@ -82,11 +183,16 @@ impl<'a> StatefulComponent for ViewProfileScreen<'a> {
                     )
                     .align(Align::Stretch),
             )
-            .signals(if state.focused_pane == FocusedPane::ServiceList {
-                SignalHandling::Forward
-            } else {
-                SignalHandling::Block
-            })
+            .signals(
+                if state.focused_pane == FocusedPane::ServiceList
+                    && !state.show_source_picker
+                    && !state.show_block_details
+                {
+                    SignalHandling::Forward
+                } else {
+                    SignalHandling::Block
+                },
+            )
             .size(list_width, list_height)
             .pos(0, 0);
 
@@ -99,39 +205,136 @@ impl<'a> StatefulComponent for ViewProfileScreen<'a> {
             )
         };
 
-        context.render_component(service_list_component)?;
+        let selected_service = context.render_component(service_list_component)?;
+
+        // Errors are considered seen once the output pane is focused while showing the output of the service
+        if state.focused_pane == FocusedPane::OutputArea {
+            self.system_state.update_all_services(|(_, service)| {
+                if service.output_enabled {
+                    service.unseen_errors = 0;
+                }
+            });
+        }
+
+        // The problems pane is displayed below the output pane, growing with the number of problems up to a third of
+        // the available height
+        let problems_height = if state.show_problems {
+            (ProblemsPane::count_problems(self.system_state) as u16 + 2)
+                .clamp(3, (self_size.height / 3).max(3))
+        } else {
+            0
+        };
+        let output_height = self_size.height.saturating_sub(problems_height);
+        let output_color = if state.focused_pane == FocusedPane::OutputArea {
+            focused_color
+        } else {
+            unfocused_color
+        };
+        let output_title = format!(
+            "Wrap: {wrap} | Level: {level} | Time: {timestamps}",
+            timestamps = state.timestamps,
+            wrap = if state.wrap_output { "Y" } else { "N" },
+            level = state
+                .min_level
+                .map(|level| format!("{level}+"))
+                .unwrap_or("All".to_owned()),
+        );
 
-        context.render_component(
+        let output = context.render_component(
             RenderArgs::new(
                 Cell::new(
                     output_pane::OutputPane {
                         wrap_output: state.wrap_output,
+                        min_level: state.min_level,
+                        timestamps: state.timestamps,
+                        reveal_line: state.reveal_line.take(),
                         system_state: self.system_state,
                     }
                     .with_zero_measurement(),
                 )
-                .border(
-                    if state.focused_pane == FocusedPane::OutputArea {
-                        focused_color
-                    } else {
-                        unfocused_color
-                    },
-                    if state.wrap_output {
-                        "Wrap: Y"
-                    } else {
-                        "Wrap: N"
-                    },
-                )
+                .border(output_color, &output_title)
                 .align(Align::Stretch),
             )
-            .signals(if state.focused_pane == FocusedPane::OutputArea {
-                SignalHandling::Forward
-            } else {
-                SignalHandling::Block
-            })
-            .size(self_size.width - list_size.width, self_size.height)
+            .signals(
+                if state.focused_pane == FocusedPane::OutputArea && !state.show_source_picker {
+                    SignalHandling::Forward
+                } else {
+                    SignalHandling::Block
+                },
+            )
+            .size(self_size.width - list_size.width, output_height)
             .pos(list_size.width, 0),
         )?;
+        state.output_captures_input = output.captures_input;
+        // Appended to the title once the pane is rendered, as following the output is paused by its inputs
+        if let Some(new_lines) = output.new_lines {
+            let paused = format!(" | Paused \u{2014} {new_lines} new lines");
+            let title_width = output_title.chars().count() as u16;
+            let width = (self_size.width - list_size.width)
+                .saturating_sub(title_width + 2)
+                .min(paused.chars().count() as u16);
+            context.render_component(
+                RenderArgs::new(Text::new(paused).fg(output_color))
+                    .size(width, 1u16)
+                    .pos(list_size.width + 1 + title_width, 0),
+            )?;
+        }
+        if output.editor_command.is_some() {
+            self.system_state.editor_request = output.editor_command;
+        }
+
+        if state.show_problems {
+            let selected = context.render_component(
+                RenderArgs::new(
+                    Cell::new(
+                        ProblemsPane {
+                            system_state: self.system_state,
+                            show_selection: state.focused_pane == FocusedPane::Problems,
+                        }
+                        .with_zero_measurement(),
+                    )
+                    .border(
+                        if state.focused_pane == FocusedPane::Problems {
+                            focused_color
+                        } else {
+                            unfocused_color
+                        },
+                        &format!(
+                            "Problems ({count})",
+                            count = ProblemsPane::count_problems(self.system_state)
+                        ),
+                    )
+                    .align(Align::Stretch),
+                )
+                .signals(
+                    if state.focused_pane == FocusedPane::Problems && !state.show_source_picker {
+                        SignalHandling::Forward
+                    } else {
+                        SignalHandling::Block
+                    },
+                )
+                .size(self_size.width - list_size.width, problems_height)
+                .pos(list_size.width, output_height),
+            )?;
+
+            // Show the output the selected problem was extracted from
+            if let Some(output_index) = selected {
+                state.reveal_line = Some(output_index);
+                state.focused_pane = FocusedPane::OutputArea;
+            }
+        }
+
+        if state.show_source_picker {
+            context.render_component(RenderArgs::new(source_picker::SourcePicker {
+                system_state: self.system_state,
+            }))?;
+        }
+        if state.show_block_details {
+            context.render_component(RenderArgs::new(block_details::BlockDetails {
+                system_state: self.system_state,
+                service_index: selected_service,
+            }))?;
+        }
 
         Ok(())
     }
@@ -141,10 +344,19 @@ impl<'a> StatefulComponent for ViewProfileScreen<'a> {
 pub struct ViewProfileScreenState {
     focused_pane: FocusedPane,
     wrap_output: bool,
+    min_level: Option<LogLevel>,
+    timestamps: TimestampMode,
+    output_captures_input: bool,
+    show_source_picker: bool,
+    show_block_details: bool,
+    show_problems: bool,
+    /// Output line to scroll to on the next frame
+    reveal_line: Option<u128>,
 }
 #[derive(Eq, PartialEq, Clone, Copy, Debug, Hash, Default)]
 pub enum FocusedPane {
     #[default]
     ServiceList,
     OutputArea,
+    Problems,
 }
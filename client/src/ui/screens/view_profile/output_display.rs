@@ -39,7 +39,7 @@ impl Component for OutputDisplay {
                         line.parts
                             .iter()
                             .flat_map(|part| {
-                                let LinePart { text, color } = part;
+                                let LinePart { text, color, bg } = part;
                                 let mut whitespace_split: Vec<Vec<&str>> = Vec::new();
                                 let mut last_whitespace = false;
                                 UnicodeSegmentation::graphemes(text.as_str(), true).for_each(
@@ -76,6 +76,7 @@ impl Component for OutputDisplay {
                                                 })
                                                 .collect(),
                                             color: *color,
+                                            bg: *bg,
                                         }
                                     })
                                     .collect::<Vec<LinePart>>()
@@ -101,6 +102,7 @@ impl Component for OutputDisplay {
                                                     Some(LinePart {
                                                         text: String::from("\u{21AA}"),
                                                         color: Color::Rgb(120, 120, 120).into(),
+                                                        bg: None,
                                                     })
                                                 } else {
                                                     None
@@ -141,6 +143,7 @@ impl Component for OutputDisplay {
                                         .collect::<Vec<&str>>()
                                         .concat(),
                                     color: part.color,
+                                    bg: part.bg,
                                 };
                                 remaining_to_drop =
                                     remaining_to_drop.saturating_sub(part.text.len());
@@ -167,7 +170,7 @@ impl Component for OutputDisplay {
             let mut inner_flow = Flow::new().dir(Dir::LeftRight);
             for part in line {
                 inner_flow = inner_flow.element(
-                    Text::new(part.text).fg(part.color),
+                    Text::new(part.text).fg(part.color).bg(part.bg),
                     FlowableArgs { fill: false },
                 )
             }
@@ -198,4 +201,5 @@ pub struct OutputLine {
 pub struct LinePart {
     pub text: String,
     pub color: Option<Color>,
+    pub bg: Option<Color>,
 }
@@ -0,0 +1,239 @@
+use crate::models::{FieldCondition, OutputKey, OutputLine, StructuredLine};
+use crate::ui::screens::view_profile::query_input::compile_query;
+use regex::Regex;
+use std::sync::LazyLock;
+
+static TERMS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\S+").unwrap());
+
+/// Live filter for the output pane. Only lines matching the filter are displayed.
+///
+/// The filter text consists of whitespace-separated terms. Terms of the form `source:<name>` restrict the output to
+/// the sources with the given names. Terms of the form `<field><op><value>`, such as `level>=warn` or `traceId=abc`,
+/// are conditions on the fields of JSON lines, while lines that aren't JSON have to contain them as text instead. The
+/// rest of the text is the query that lines must match.
+#[derive(Default)]
+pub struct OutputFilter {
+    /// The filter as typed by the user
+    pub text: String,
+    /// If set, the query is interpreted as a regular expression instead of plain text
+    pub regex_mode: bool,
+    /// Whether the filter is currently being edited, in which case all key presses are consumed as text input
+    pub editing: bool,
+    /// Filter text and mode from before editing was started, restored if editing is cancelled
+    previous: (String, bool),
+    query: Option<Regex>,
+    sources: Vec<String>,
+    /// Field conditions along with the query matching the term as text, used for lines that aren't structured
+    conditions: Vec<(FieldCondition, Option<Regex>)>,
+    pub invalid_query: bool,
+}
+impl OutputFilter {
+    pub fn start(&mut self) {
+        self.previous = (self.text.clone(), self.regex_mode);
+        self.editing = true;
+    }
+
+    pub fn cancel(&mut self) {
+        (self.text, self.regex_mode) = std::mem::take(&mut self.previous);
+        self.editing = false;
+        self.update();
+    }
+
+    pub fn confirm(&mut self) {
+        self.editing = false;
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self {
+            regex_mode: self.regex_mode,
+            ..Self::default()
+        };
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.editing || !self.text.is_empty()
+    }
+
+    /// Parses the filter after the text or the mode has changed. Source terms and field conditions are cut out of the
+    /// text along with the whitespace following them, while the rest is used as the query unchanged, so that queries
+    /// can still contain repeated spaces or regexes with spaces in them.
+    pub fn update(&mut self) {
+        let mut query = String::new();
+        let mut query_start = 0;
+        let mut invalid_condition = false;
+        self.sources.clear();
+        self.conditions.clear();
+        for term in TERMS.find_iter(&self.text) {
+            match term.as_str().strip_prefix("source:") {
+                Some(source) if !source.is_empty() => self.sources.push(source.to_owned()),
+                Some(_) => {}
+                None => match FieldCondition::parse(term.as_str()) {
+                    Some(condition) if condition.is_valid() => {
+                        let fallback = compile_query(term.as_str(), self.regex_mode);
+                        invalid_condition |= fallback.is_err();
+                        self.conditions.push((condition, fallback.unwrap_or(None)));
+                    }
+                    Some(_) => invalid_condition = true,
+                    None => continue,
+                },
+            }
+            query.push_str(&self.text[query_start..term.start()]);
+            query_start = self.text[term.end()..]
+                .find(|char: char| !char.is_whitespace())
+                .map_or(self.text.len(), |offset| term.end() + offset);
+        }
+        if query_start < self.text.len() {
+            query.push_str(&self.text[query_start..]);
+        } else {
+            // Don't leave the whitespace before a term at the end behind
+            query.truncate(query.trim_end().len());
+        }
+
+        let result = compile_query(&query, self.regex_mode);
+        self.invalid_query = result.is_err() || invalid_condition;
+        self.query = result.unwrap_or(None);
+    }
+
+    /// Checks whether the line passes the filter. An invalid query is ignored, so that the output doesn't disappear
+    /// while a regex is being typed.
+    pub fn is_match(&self, key: &OutputKey, line: &OutputLine) -> bool {
+        (self.sources.is_empty() || self.sources.contains(&key.source_name))
+            && self
+                .query
+                .as_ref()
+                .map(|query| query.is_match(&line.value))
+                .unwrap_or(true)
+            && self.matches_conditions(line)
+    }
+
+    fn matches_conditions(&self, line: &OutputLine) -> bool {
+        let mut parsed = None;
+        self.conditions.iter().all(|(condition, fallback)| {
+            if condition.is_level_condition() {
+                return condition.matches_level(line.level);
+            }
+
+            // Lines of sources with the JSON format are parsed when they're stored, while JSON lines of other sources are
+            // parsed here. Conditions such as `status=500` are just as likely to be meant as text on plain output.
+            let structured = match &line.structured {
+                Some(structured) => Some(structured.as_ref()),
+                None => parsed
+                    .get_or_insert_with(|| StructuredLine::parse(&line.value))
+                    .as_ref(),
+            };
+            match structured {
+                Some(structured) => condition.matches(structured),
+                None => fallback
+                    .as_ref()
+                    .is_none_or(|fallback| fallback.is_match(&line.value)),
+            }
+        })
+    }
+
+    /// Text displayed in the filter prompt at the bottom of the output pane.
+    pub fn prompt(&self) -> String {
+        let mut prompt = format!(
+            "filter{mode}: {text}",
+            mode = if self.regex_mode { " (regex)" } else { "" },
+            text = self.text
+        );
+        if self.editing {
+            prompt.push('_');
+        }
+        if self.invalid_query {
+            prompt.push_str("  [invalid query]");
+        }
+
+        prompt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutputFilter;
+    use crate::config::{LogLevel, ServiceId};
+    use crate::models::{OutputKey, OutputKind, OutputLine, StructuredLine};
+    use chrono::Local;
+
+    fn key(source_name: &str) -> OutputKey {
+        OutputKey {
+            service_id: Some(ServiceId::new("service")),
+            source_name: source_name.to_string(),
+            kind: OutputKind::ExtProcess,
+        }
+    }
+
+    fn line(value: &str, level: Option<LogLevel>, json_source: bool) -> OutputLine {
+        OutputLine {
+            value: value.to_string(),
+            index: 1,
+            timestamp: Local::now(),
+            level,
+            color: None,
+            structured: json_source
+                .then(|| StructuredLine::parse(value).map(Box::new))
+                .flatten(),
+            repeats: None,
+        }
+    }
+
+    fn filter(text: &str) -> OutputFilter {
+        let mut filter = OutputFilter {
+            text: text.to_string(),
+            ..OutputFilter::default()
+        };
+        filter.update();
+        filter
+    }
+
+    #[test]
+    fn test_query_and_sources() {
+        let filter = filter("source:api connection refused");
+        assert!(!filter.invalid_query);
+        assert!(filter.is_match(&key("api"), &line("Connection refused", None, false)));
+        assert!(!filter.is_match(&key("db"), &line("connection refused", None, false)));
+        assert!(!filter.is_match(&key("api"), &line("connection reset", None, false)));
+
+        // The query is used as typed apart from the source terms
+        let filter = self::filter("source:api  a  b source:db");
+        assert!(filter.is_match(&key("db"), &line("a  b", None, false)));
+        assert!(!filter.is_match(&key("db"), &line("a b", None, false)));
+
+        let mut filter = OutputFilter {
+            text: "source:api ^GET /users \\d+ status=500".to_string(),
+            regex_mode: true,
+            ..OutputFilter::default()
+        };
+        filter.update();
+        assert!(!filter.invalid_query);
+        assert!(filter.is_match(&key("api"), &line("GET /users 12 status=500", None, false)));
+        assert!(!filter.is_match(&key("api"), &line("GET /users 12 status=404", None, false)));
+        assert!(!filter.is_match(&key("api"), &line("GET /users/12 status=500", None, false)));
+    }
+
+    #[test]
+    fn test_field_conditions() {
+        let filter = filter("status=500");
+        let json = r#"{"status":500,"msg":"failed"}"#;
+        assert!(filter.is_match(&key("api"), &line(json, None, true)));
+        // JSON lines of plain sources are still compared by their fields
+        assert!(filter.is_match(&key("api"), &line(json, None, false)));
+        assert!(!filter.is_match(&key("api"), &line(r#"{"status":404}"#, None, true)));
+        // Lines that aren't JSON have to contain the term as text
+        assert!(filter.is_match(&key("api"), &line("GET /users status=500", None, false)));
+        assert!(!filter.is_match(&key("api"), &line("GET /users status=404", None, false)));
+    }
+
+    #[test]
+    fn test_level_conditions() {
+        let filter = filter("level>=warn");
+        assert!(filter.is_match(
+            &key("api"),
+            &line("disk full", Some(LogLevel::Error), false)
+        ));
+        assert!(!filter.is_match(&key("api"), &line("started", Some(LogLevel::Info), false)));
+        assert!(!filter.is_match(&key("api"), &line("level>=warn", None, false)));
+
+        assert!(self::filter("level>=loud").invalid_query);
+    }
+}
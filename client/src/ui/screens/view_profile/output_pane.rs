@@ -1,22 +1,379 @@
-use crate::models::{OutputKind, get_active_outputs};
+use crate::config::{BlockId, LogLevel};
+use crate::models::OutputLine as StoredLine;
+use crate::models::{
+    ExportResult, FileReference, OutputExport, OutputKey, OutputKind, get_active_outputs,
+    resolve_export_path,
+};
 use crate::system_state::SystemState;
+use crate::ui::inputs::{
+    ATTR_KEY_EXPORT_OUTPUT, ATTR_KEY_FILTER, ATTR_KEY_JUMP_TO_TIME, ATTR_KEY_OPEN_IN_EDITOR,
+    ATTR_KEY_SEARCH, ATTR_KEY_SEARCH_NEXT, ATTR_KEY_SEARCH_PREV, ATTR_KEY_TOGGLE_EXPAND_LINE,
+    ATTR_KEY_TOGGLE_FOLLOW,
+};
 use crate::ui::screens::view_profile::output_display::{LinePart, OutputDisplay, OutputLine};
+use crate::ui::screens::view_profile::output_filter::OutputFilter;
+use crate::ui::screens::view_profile::output_search::OutputSearch;
+use crate::ui::screens::view_profile::query_input::{QueryInput, process_query_input};
+use crate::ui::screens::view_profile::timestamps::{
+    TimestampMode, format_timestamp, parse_jump_target,
+};
+use chrono::{DateTime, Local};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use log::error;
 use ratatui::style::Color;
 use std::cmp::max;
+use std::collections::HashSet;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
 use ui::component::{
-    ATTR_KEY_NAV_DOWN, ATTR_KEY_NAV_DOWN_LARGE, ATTR_KEY_NAV_LEFT, ATTR_KEY_NAV_LEFT_LARGE,
-    ATTR_KEY_NAV_RIGHT, ATTR_KEY_NAV_RIGHT_LARGE, ATTR_KEY_NAV_TO_END, ATTR_KEY_NAV_TO_START,
-    ATTR_KEY_NAV_UP, ATTR_KEY_NAV_UP_LARGE, Dir, Flow, FlowableArgs, Spinner, StatefulComponent,
+    ATTR_KEY_CANCEL, ATTR_KEY_NAV_DOWN, ATTR_KEY_NAV_DOWN_LARGE, ATTR_KEY_NAV_LEFT,
+    ATTR_KEY_NAV_LEFT_LARGE, ATTR_KEY_NAV_RIGHT, ATTR_KEY_NAV_RIGHT_LARGE, ATTR_KEY_NAV_TO_END,
+    ATTR_KEY_NAV_TO_START, ATTR_KEY_NAV_UP, ATTR_KEY_NAV_UP_LARGE, Dir, Flow, FlowableArgs,
+    Spinner, StatefulComponent, Text,
 };
 use ui::input::KeyMatcherQueryable;
 use ui::{FrameContext, RenderArgs, UIResult};
 
 pub struct OutputPane<'a> {
     pub wrap_output: bool,
+    /// If set, only lines of at least this level are displayed. Lines without a level are treated as info-level.
+    pub min_level: Option<LogLevel>,
+    pub timestamps: TimestampMode,
+    /// If set, the view is scrolled to show this line at the top
+    pub reveal_line: Option<u128>,
     pub system_state: &'a SystemState,
 }
 impl<'a> OutputPane<'a> {
+    fn is_visible(&self, filter: &OutputFilter, key: &OutputKey, line: &StoredLine) -> bool {
+        self.min_level
+            .map(|min_level| line.level.unwrap_or(LogLevel::Info) >= min_level)
+            .unwrap_or(true)
+            && filter.is_match(key, line)
+    }
+
+    /// Finds the index of the closest visible line matching the search query, searching either towards newer lines
+    /// starting after `from`, or towards older lines starting at `from`.
+    fn find_match(
+        &self,
+        filter: &OutputFilter,
+        search: &OutputSearch,
+        from: Option<u128>,
+        newer: bool,
+    ) -> Option<u128> {
+        let active_outputs = get_active_outputs(self.system_state);
+        let filter = |key: &OutputKey, line: &StoredLine| {
+            self.is_visible(filter, key, line) && search.is_match(&line.value)
+        };
+
+        if newer {
+            // There's nothing newer than the end of the output
+            let from = from?;
+            self.system_state
+                .output_store
+                .query_lines_from(1, Some(from + 1), &active_outputs, filter)
+                .first()
+                .map(|(_, line)| line.index)
+        } else {
+            self.system_state
+                .output_store
+                .query_lines_to(1, from, &active_outputs, filter)
+                .first()
+                .map(|(_, line)| line.index)
+        }
+    }
+
+    /// Handles the text input while editing the search query.
+    fn process_search_input(
+        &self,
+        context: &mut FrameContext,
+        state: &mut OutputPaneState,
+    ) -> UIResult<()> {
+        let search = &mut state.search;
+        match process_query_input(context, &mut search.text, &mut search.regex_mode)? {
+            QueryInput::Unchanged => {}
+            QueryInput::Changed => {
+                search.update_query();
+                // Incremental search: always search from where the search was started, so that the view doesn't drift
+                // while the query is being typed.
+                let found = self.find_match(&state.filter, search, search.origin, false);
+                search.no_matches = search.query.is_some() && found.is_none();
+                state.pos_vert = found.or(search.origin);
+            }
+            QueryInput::Confirmed => {
+                search.editing = false;
+                if search.query.is_none() {
+                    search.clear();
+                }
+            }
+            QueryInput::Cancelled => {
+                state.pos_vert = search.origin;
+                search.clear();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handles the text input while editing the filter. The filter is applied live while typing.
+    fn process_filter_input(
+        &self,
+        context: &mut FrameContext,
+        state: &mut OutputPaneState,
+    ) -> UIResult<()> {
+        let filter = &mut state.filter;
+        match process_query_input(context, &mut filter.text, &mut filter.regex_mode)? {
+            QueryInput::Unchanged => {}
+            QueryInput::Changed => filter.update(),
+            QueryInput::Confirmed => filter.confirm(),
+            QueryInput::Cancelled => filter.cancel(),
+        }
+        // The scroll position may point to a line that's no longer visible, so the view follows the end of the
+        // output while the filter is being edited
+        state.pos_vert = None;
+
+        Ok(())
+    }
+
+    /// Handles the text input while entering a time to jump to.
+    fn process_jump_input(
+        &self,
+        context: &mut FrameContext,
+        state: &mut OutputPaneState,
+    ) -> UIResult<()> {
+        let Some(text) = state.jump_text.as_mut() else {
+            return Ok(());
+        };
+        // Times have no regex mode, so toggling it has no effect
+        let mut regex_mode = false;
+        match process_query_input(context, text, &mut regex_mode)? {
+            QueryInput::Unchanged | QueryInput::Changed => {}
+            QueryInput::Confirmed => {
+                let text = state.jump_text.take().unwrap_or_default();
+                match parse_jump_target(&text, Local::now()) {
+                    Some(target) => self.jump_to_time(context, state, target),
+                    None => state.status = Some(format!("Invalid time: {text}")),
+                }
+            }
+            QueryInput::Cancelled => state.jump_text = None,
+        }
+
+        Ok(())
+    }
+
+    /// Scrolls the view so that the first visible line received at or after `target` is at the top.
+    fn jump_to_time(
+        &self,
+        context: &FrameContext,
+        state: &mut OutputPaneState,
+        target: DateTime<Local>,
+    ) {
+        let active_outputs = get_active_outputs(self.system_state);
+        let first = self
+            .system_state
+            .output_store
+            .query_lines_from(1, None, &active_outputs, |key, line| {
+                line.timestamp >= target && self.is_visible(&state.filter, key, line)
+            })
+            .first()
+            .map(|(_, line)| line.index);
+
+        match first {
+            Some(first) => self.scroll_to_top(context, state, first),
+            None => {
+                state.status = Some(format!(
+                    "No output after {}",
+                    target.format("%Y-%m-%d %H:%M:%S")
+                ))
+            }
+        }
+    }
+
+    /// Scrolls the view so that the first visible line with an index of at least `index` is at the top.
+    fn scroll_to_top(&self, context: &FrameContext, state: &mut OutputPaneState, index: u128) {
+        let height = context.size().height as usize;
+        let lines = self.system_state.output_store.query_lines_from(
+            height,
+            Some(index),
+            &get_active_outputs(self.system_state),
+            |key, line| self.is_visible(&state.filter, key, line),
+        );
+        // If the remaining output fits into the view, keep following the end of it
+        state.pos_vert = if lines.len() == height {
+            lines.last().map(|(_, line)| line.index)
+        } else {
+            None
+        };
+    }
+
+    /// The line at the bottom of the view, which is the target of actions on individual lines.
+    fn bottom_line(&self, state: &OutputPaneState) -> Option<(&OutputKey, &StoredLine)> {
+        self.system_state
+            .output_store
+            .query_lines_to(
+                1,
+                state.pos_vert,
+                &get_active_outputs(self.system_state),
+                |key, line| self.is_visible(&state.filter, key, line),
+            )
+            .pop()
+    }
+
+    /// Expands or collapses the line at the bottom of the view, if it's a structured line.
+    fn toggle_expanded(&self, state: &mut OutputPaneState) {
+        let bottom_line = self
+            .bottom_line(state)
+            .filter(|(_, line)| line.occurrences() > 1 || line.structured.is_some())
+            .map(|(_, line)| line.index);
+
+        match bottom_line {
+            Some(index) => {
+                if !state.expanded_lines.remove(&index) {
+                    state.expanded_lines.insert(index);
+                }
+            }
+            None => {
+                state.status =
+                    Some("The last line in view is neither a JSON line nor repeated".to_string())
+            }
+        }
+    }
+
+    /// Builds the editor command for the file referenced by the line at the bottom of the view. Relative paths are
+    /// resolved against the workdir of the service that produced the line.
+    fn editor_command(&self, state: &mut OutputPaneState) -> Option<Vec<String>> {
+        let reference = self.bottom_line(state).and_then(|(key, line)| {
+            let workdir = key
+                .service_id
+                .as_ref()
+                .and_then(|service_id| self.system_state.get_service(service_id))
+                .map(|service| service.definition.workdir.as_str())
+                .unwrap_or(".");
+            FileReference::find(&line.value, workdir)
+        });
+        let Some(reference) = reference else {
+            state.status = Some("The last line in view doesn't reference a file".to_string());
+            return None;
+        };
+
+        let command =
+            reference.editor_command(self.system_state.config.settings.editor_command.as_deref());
+        if command.is_none() {
+            state.status =
+                Some("No editor configured, set $EDITOR or the editor_command setting".to_string());
+        }
+        command
+    }
+
+    /// Moves the view to the oldest retained lines if the lines it was scrolled to have been dropped due to the
+    /// retention limits. Since the position refers to the index of a line, it is otherwise unaffected by eviction.
+    fn clamp_scroll_position(&self, context: &FrameContext, state: &mut OutputPaneState) {
+        let Some(pos_vert) = state.pos_vert else {
+            return;
+        };
+
+        let height = context.size().height as usize;
+        let lines = self.system_state.output_store.query_lines_from(
+            height,
+            None,
+            &get_active_outputs(self.system_state),
+            |key, line| self.is_visible(&state.filter, key, line),
+        );
+        if lines.len() < height {
+            // All remaining lines fit into the view
+            state.pos_vert = None;
+        } else if let Some((_, line)) = lines.last()
+            && line.index > pos_vert
+        {
+            state.pos_vert = Some(line.index);
+        }
+    }
+
+    /// Handles the text input while entering the path to export the output to.
+    fn process_export_input(
+        &self,
+        context: &mut FrameContext,
+        state: &mut OutputPaneState,
+    ) -> UIResult<()> {
+        let Some(text) = state.export_path.as_mut() else {
+            return Ok(());
+        };
+        // Paths have no regex mode, so toggling it has no effect
+        let mut regex_mode = false;
+        match process_query_input(context, text, &mut regex_mode)? {
+            QueryInput::Unchanged | QueryInput::Changed => {}
+            QueryInput::Confirmed => {
+                let text = state.export_path.take().unwrap_or_default();
+                if !text.trim().is_empty() {
+                    let path = Path::new(&self.system_state.resolved_data_dir).join(text.trim());
+                    self.export(state, path);
+                }
+            }
+            QueryInput::Cancelled => state.export_path = None,
+        }
+
+        Ok(())
+    }
+
+    /// Exports the active outputs using the export settings, optionally applying the current filter. The lines are
+    /// collected right away, while the file is written in the background.
+    fn export(&self, state: &mut OutputPaneState, path: PathBuf) {
+        let settings = &self.system_state.config.settings.output.export;
+        let active_outputs = get_active_outputs(self.system_state);
+        let export = if settings.apply_filter {
+            OutputExport::collect(
+                self.system_state,
+                &active_outputs,
+                settings,
+                path,
+                |key, line| self.is_visible(&state.filter, key, line),
+            )
+        } else {
+            OutputExport::collect(
+                self.system_state,
+                &active_outputs,
+                settings,
+                path,
+                |_, _| true,
+            )
+        };
+
+        state.status = Some(format!("Exporting to {}...", export.path.display()));
+        let (result_tx, result_rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = result_tx.send(export.write());
+        });
+        state.export_result = Some(result_rx);
+    }
+
+    /// Displays the result of the running export once it is done.
+    fn check_export_result(&self, state: &mut OutputPaneState) {
+        let result = match state
+            .export_result
+            .as_ref()
+            .map(|result_rx| result_rx.try_recv())
+        {
+            Some(Ok(result)) => result,
+            Some(Err(TryRecvError::Disconnected)) => {
+                Err(io::Error::other("export was interrupted"))
+            }
+            Some(Err(TryRecvError::Empty)) | None => return,
+        };
+
+        state.export_result = None;
+        state.status = Some(match result {
+            Ok(ExportResult { path, num_lines }) => {
+                format!("Exported {num_lines} lines to {}", path.display())
+            }
+            Err(err) => {
+                error!("Failed to export output: {err}");
+                format!("Export failed: {err}")
+            }
+        });
+    }
+
     fn hash_name(name: &str) -> usize {
         // Hash the name to obtain a color for it
         let mut hasher = DefaultHasher::new();
@@ -29,6 +386,99 @@ impl<'a> OutputPane<'a> {
         context: &mut FrameContext,
         state: &mut OutputPaneState,
     ) -> UIResult<()> {
+        // Status messages are displayed until the next key press
+        if context
+            .signals()
+            .matching::<KeyEvent>()
+            .iter()
+            .any(|event| event.kind == KeyEventKind::Press)
+        {
+            state.status = None;
+        }
+
+        if state.search.editing {
+            return self.process_search_input(context, state);
+        } else if state.filter.editing {
+            return self.process_filter_input(context, state);
+        } else if state.jump_text.is_some() {
+            return self.process_jump_input(context, state);
+        } else if state.export_path.is_some() {
+            return self.process_export_input(context, state);
+        }
+
+        if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_SEARCH)?)
+        {
+            state.search.start(state.pos_vert);
+            return Ok(());
+        } else if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_FILTER)?)
+        {
+            state.filter.start();
+            return Ok(());
+        } else if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_EXPORT_OUTPUT)?)
+        {
+            let settings = &self.system_state.config.settings.output.export;
+            let path = resolve_export_path(self.system_state, settings);
+            state.export_path = Some(path.to_string_lossy().into_owned());
+            return Ok(());
+        } else if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_JUMP_TO_TIME)?)
+        {
+            state.jump_text = Some(String::new());
+            return Ok(());
+        } else if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_TOGGLE_EXPAND_LINE)?)
+        {
+            self.toggle_expanded(state);
+            return Ok(());
+        } else if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_OPEN_IN_EDITOR)?)
+        {
+            state.editor_command = self.editor_command(state);
+            return Ok(());
+        } else if state.search.query.is_some() {
+            let newer = context
+                .signals()
+                .is_key_pressed(context.req_attr(ATTR_KEY_SEARCH_NEXT)?);
+            let older = context
+                .signals()
+                .is_key_pressed(context.req_attr(ATTR_KEY_SEARCH_PREV)?);
+            if newer || older {
+                // The line at the bottom of the view is the reference point, as that's where matches are scrolled to
+                let from = if older {
+                    state.pos_vert.map(|pos| pos.saturating_sub(1))
+                } else {
+                    state.pos_vert
+                };
+                let found = self.find_match(&state.filter, &state.search, from, newer);
+                state.search.no_matches = found.is_none();
+                if found.is_some() {
+                    state.pos_vert = found;
+                }
+                return Ok(());
+            } else if context
+                .signals()
+                .is_key_pressed(context.req_attr(ATTR_KEY_CANCEL)?)
+            {
+                state.search.clear();
+            }
+        } else if state.filter.is_active()
+            && context
+                .signals()
+                .is_key_pressed(context.req_attr(ATTR_KEY_CANCEL)?)
+        {
+            state.filter.clear();
+            state.pos_vert = None;
+        }
+
         let nav_down = if context
             .signals()
             .is_key_pressed(context.req_attr(ATTR_KEY_NAV_DOWN)?)
@@ -89,20 +539,31 @@ impl<'a> OutputPane<'a> {
             .is_key_pressed(context.req_attr(ATTR_KEY_NAV_TO_START)?)
         {
             let active_outputs = get_active_outputs(self.system_state);
-            state.pos_vert = Some(
-                self.system_state
-                    .output_store
-                    .query_lines_from(context.size().height as usize, None, &active_outputs)
-                    .last()
-                    .unwrap()
-                    .1
-                    .index,
-            );
+            state.pos_vert = self
+                .system_state
+                .output_store
+                .query_lines_from(
+                    context.size().height as usize,
+                    None,
+                    &active_outputs,
+                    |key, line| self.is_visible(&state.filter, key, line),
+                )
+                .last()
+                .map(|(_, line)| line.index);
         } else if context
             .signals()
             .is_key_pressed(context.req_attr(ATTR_KEY_NAV_TO_END)?)
         {
             state.pos_vert = None;
+        } else if context
+            .signals()
+            .is_key_pressed(context.req_attr(ATTR_KEY_TOGGLE_FOLLOW)?)
+        {
+            state.pos_vert = match state.pos_vert {
+                Some(_) => None,
+                // Pause at the line that's currently at the bottom of the view
+                None => self.bottom_line(state).map(|(_, line)| line.index),
+            };
         } else if let Some(amount) = nav_up {
             let active_outputs = get_active_outputs(self.system_state);
             // Prevent users from scrolling past the first line of output
@@ -110,11 +571,15 @@ impl<'a> OutputPane<'a> {
             let min_index = self
                 .system_state
                 .output_store
-                .query_lines_from(context.size().height as usize, None, &active_outputs)
+                .query_lines_from(
+                    context.size().height as usize,
+                    None,
+                    &active_outputs,
+                    |key, line| self.is_visible(&state.filter, key, line),
+                )
                 .last()
-                .unwrap()
-                .1
-                .index;
+                .map(|(_, line)| line.index)
+                .unwrap_or(0);
 
             state.pos_vert = self
                 .system_state
@@ -123,20 +588,26 @@ impl<'a> OutputPane<'a> {
                     amount + if state.pos_vert.is_none() { 0 } else { 1 },
                     state.pos_vert,
                     &active_outputs,
+                    |key, line| self.is_visible(&state.filter, key, line),
                 )
                 .first()
                 .map(|(_, line)| max(line.index, min_index));
         } else if let Some(amount) = nav_down {
+            let auto_resume = self.system_state.config.settings.output.auto_resume_follow;
             state.pos_vert = state.pos_vert.and_then(|pos| {
                 let lines = self.system_state.output_store.query_lines_from(
-                    amount + 1,
+                    amount + 2,
                     Some(pos),
                     &get_active_outputs(self.system_state),
+                    |key, line| self.is_visible(&state.filter, key, line),
                 );
-                if lines.len() == amount + 1 {
-                    lines.last().map(|(_, line)| line.index)
-                } else {
+                if lines.len() > amount + 1 {
+                    lines.get(amount).map(|(_, line)| line.index)
+                } else if auto_resume {
+                    // Reaching the newest line resumes following the output
                     None
+                } else {
+                    lines.last().map(|(_, line)| line.index)
                 }
             });
         }
@@ -147,14 +618,40 @@ impl<'a> OutputPane<'a> {
     }
 }
 
+#[derive(Default)]
+pub struct OutputPaneOutput {
+    /// Whether the pane is currently capturing all key presses as text input
+    pub captures_input: bool,
+    /// Command for opening a file referenced in the output in an editor
+    pub editor_command: Option<Vec<String>>,
+    /// Number of visible lines received since following the output was paused, if it is paused
+    pub new_lines: Option<usize>,
+}
+
 #[derive(Default)]
 pub struct OutputPaneState {
     pub pos_horiz: u64,
     pub pos_vert: Option<u128>,
+    pub search: OutputSearch,
+    pub filter: OutputFilter,
+    /// Indices of the structured lines that are expanded to show the full object
+    pub expanded_lines: HashSet<u128>,
+    /// Index of the next line and the time at which following the output was paused
+    paused_at: Option<(u128, DateTime<Local>)>,
+    /// Editor command requested by the last key press, handed to the parent
+    editor_command: Option<Vec<String>>,
+    /// Time being entered to jump to, if any
+    pub jump_text: Option<String>,
+    /// Path being entered to export the output to, if any
+    pub export_path: Option<String>,
+    /// Receives the result of the export being written in the background
+    export_result: Option<Receiver<io::Result<ExportResult>>>,
+    /// Message about the result of the last action, such as an export
+    pub status: Option<String>,
 }
 
 impl<'a> StatefulComponent for OutputPane<'a> {
-    type Output = ();
+    type Output = OutputPaneOutput;
     type State = OutputPaneState;
 
     fn state_id(&self) -> &str {
@@ -163,6 +660,25 @@ impl<'a> StatefulComponent for OutputPane<'a> {
 
     fn render(self, context: &mut FrameContext, state: &mut Self::State) -> UIResult<Self::Output> {
         self.process_inputs(context, state)?;
+        self.check_export_result(state);
+        if let Some(index) = self.reveal_line {
+            self.scroll_to_top(context, state, index);
+        }
+        self.clamp_scroll_position(context, state);
+
+        // Keep track of when following the output was paused, to count the lines received since then
+        let active_outputs = get_active_outputs(self.system_state);
+        let output_store = &self.system_state.output_store;
+        state.paused_at = match (state.pos_vert, state.paused_at) {
+            (None, _) => None,
+            (Some(_), None) => Some((output_store.next_index(), Local::now())),
+            (Some(_), paused_at) => paused_at,
+        };
+        let new_lines = state.paused_at.map(|(paused_at, since)| {
+            output_store.count_lines_from(paused_at, since, &active_outputs, |key, line| {
+                self.is_visible(&state.filter, key, line)
+            })
+        });
 
         let theme = &self.system_state.config.settings.theme;
         let profile = self.system_state.current_profile.as_ref().unwrap();
@@ -179,9 +695,10 @@ impl<'a> StatefulComponent for OutputPane<'a> {
                         size.height as usize,
                         state.pos_vert,
                         &get_active_outputs(self.system_state),
+                        |key, line| self.is_visible(&state.filter, key, line),
                     )
                     .into_iter()
-                    .map(|(key, line)| {
+                    .flat_map(|(key, line)| {
                         let color_idx = key
                             .service_id
                             .clone()
@@ -199,49 +716,169 @@ impl<'a> StatefulComponent for OutputPane<'a> {
                             .as_ref()
                             .map(|id| id.inner().to_owned())
                             .unwrap_or(profile.definition.id.clone());
+                        let run_start = key.service_id.as_ref().and_then(|service_id| {
+                            self.system_state
+                                .get_service(service_id)
+                                .and_then(|service| {
+                                    service.get_block_start_time(
+                                        &BlockId::new(&key.source_name),
+                                        &line.timestamp,
+                                    )
+                                })
+                        });
 
-                        OutputLine {
-                            prefix: vec![
-                                LinePart {
-                                    text: match key.kind {
-                                        OutputKind::System => "i/",
-                                        OutputKind::ExtProcess => "c/",
-                                    }
-                                    .to_string(),
-                                    color: match key.kind {
-                                        // TODO move into theme
-                                        OutputKind::System => Color::Rgb(0, 180, 0),
-                                        OutputKind::ExtProcess => Color::Rgb(0, 120, 220),
-                                    }
-                                    .into(),
-                                },
-                                LinePart {
-                                    text: format!("{name}/"),
-                                    color: theme.service_colors
-                                        [color_idx % theme.service_colors.len()]
+                        let mut prefix = vec![];
+                        if let Some(timestamp) =
+                            format_timestamp(self.timestamps, &line.timestamp, run_start)
+                        {
+                            prefix.push(LinePart {
+                                text: format!("{timestamp} "),
+                                color: Some(theme.timestamp_color.0),
+                                bg: None,
+                            });
+                        }
+                        prefix.extend([
+                            LinePart {
+                                text: match key.kind {
+                                    OutputKind::System => "i/",
+                                    OutputKind::ExtProcess => "c/",
+                                }
+                                .to_string(),
+                                color: match key.kind {
+                                    // TODO move into theme
+                                    OutputKind::System => Color::Rgb(0, 180, 0),
+                                    OutputKind::ExtProcess => Color::Rgb(0, 120, 220),
+                                }
+                                .into(),
+                                bg: None,
+                            },
+                            LinePart {
+                                text: format!("{name}/"),
+                                color: theme.service_colors[color_idx % theme.service_colors.len()]
                                     .0
                                     .into(),
-                                },
-                                LinePart {
-                                    text: format!("{name} | ", name = key.source_name),
-                                    color: Some(
-                                        theme.source_colors[Self::hash_name(&key.source_name)
-                                            % theme.source_colors.len()]
-                                        .0,
-                                    ),
-                                },
-                            ],
-                            parts: vec![LinePart {
-                                text: line.value.clone(),
-                                color: None,
-                            }],
+                                bg: None,
+                            },
+                            LinePart {
+                                text: format!("{name} | ", name = key.source_name),
+                                color: Some(
+                                    theme.source_colors[Self::hash_name(&key.source_name)
+                                        % theme.source_colors.len()]
+                                    .0,
+                                ),
+                                bg: None,
+                            },
+                        ]);
+
+                        let rule_color = line.color;
+                        let level_color = line.level.map(|level| theme.log_level_color(level));
+
+                        // Structured lines are colored by their level field first
+                        let structured = line.structured.as_deref();
+                        let mut parts = match structured {
+                            Some(structured) => state.search.highlight(
+                                &structured.summary(),
+                                level_color.or(rule_color),
+                                Color::Black,
+                                theme.search_match_color.0,
+                            ),
+                            None => state.search.highlight(
+                                &line.value,
+                                rule_color.or(level_color),
+                                Color::Black,
+                                theme.search_match_color.0,
+                            ),
+                        };
+                        if line.occurrences() > 1 {
+                            parts.push(LinePart {
+                                text: format!(" (×{})", line.occurrences()),
+                                color: Some(theme.timestamp_color.0),
+                                bg: None,
+                            });
                         }
+
+                        let mut lines = vec![OutputLine { prefix, parts }];
+                        if state.expanded_lines.contains(&line.index) {
+                            let detail_line = |text: String| OutputLine {
+                                prefix: vec![LinePart {
+                                    text: "    ".to_string(),
+                                    color: None,
+                                    bg: None,
+                                }],
+                                parts: vec![LinePart {
+                                    text,
+                                    color: Some(theme.timestamp_color.0),
+                                    bg: None,
+                                }],
+                            };
+                            if let Some(structured) = structured {
+                                lines.extend(
+                                    structured
+                                        .pretty()
+                                        .lines()
+                                        .map(|pretty_line| detail_line(pretty_line.to_string())),
+                                );
+                            }
+                            if let Some(repeats) = &line.repeats {
+                                let omitted = repeats.count - repeats.recent.len();
+                                if omitted > 0 {
+                                    lines.push(detail_line(format!(
+                                        "... {omitted} more occurrences"
+                                    )));
+                                }
+                                lines.extend(repeats.recent.iter().map(|(timestamp, value)| {
+                                    let timestamp =
+                                        format_timestamp(self.timestamps, timestamp, run_start)
+                                            .map(|timestamp| format!("{timestamp} "))
+                                            .unwrap_or_default();
+                                    detail_line(format!("{timestamp}{value}"))
+                                }));
+                            }
+                        }
+
+                        lines
                     })
                     .collect(),
             },
             FlowableArgs { fill: true },
         );
 
+        if let Some(status) = &state.status {
+            flow = flow.element(Text::new(status.clone()), FlowableArgs { fill: false });
+        }
+        if state.filter.is_active() {
+            flow = flow.element(
+                Text::new(state.filter.prompt()).fg(if state.filter.invalid_query {
+                    Some(theme.error_color.0)
+                } else {
+                    None
+                }),
+                FlowableArgs { fill: false },
+            );
+        }
+        if let Some(text) = &state.jump_text {
+            flow = flow.element(
+                Text::new(format!("time: {text}_  (HH:MM[:SS] or -<duration>)")),
+                FlowableArgs { fill: false },
+            );
+        }
+        if let Some(path) = &state.export_path {
+            flow = flow.element(
+                Text::new(format!("export to: {path}_")),
+                FlowableArgs { fill: false },
+            );
+        }
+        if state.search.is_active() {
+            flow = flow.element(
+                Text::new(state.search.prompt()).fg(if state.search.invalid_query {
+                    Some(theme.error_color.0)
+                } else {
+                    None
+                }),
+                FlowableArgs { fill: false },
+            );
+        }
+
         // TODO maybe we can display this in a manner that doesn't look like its loading something?
         if state.pos_vert.is_none() {
             flow = flow.element(Spinner::new(true), FlowableArgs { fill: false });
@@ -249,6 +886,13 @@ impl<'a> StatefulComponent for OutputPane<'a> {
 
         context.render_component(RenderArgs::new(flow))?;
 
-        Ok(())
+        Ok(OutputPaneOutput {
+            captures_input: state.search.editing
+                || state.filter.editing
+                || state.jump_text.is_some()
+                || state.export_path.is_some(),
+            editor_command: state.editor_command.take(),
+            new_lines,
+        })
     }
 }
@@ -0,0 +1,120 @@
+use crate::ui::screens::view_profile::output_display::LinePart;
+use crate::ui::screens::view_profile::query_input::compile_query;
+use ratatui::style::Color;
+use regex::Regex;
+
+/// State of the incremental search in the output pane.
+#[derive(Default)]
+pub struct OutputSearch {
+    /// The query as typed by the user
+    pub text: String,
+    /// If set, the query is interpreted as a regular expression instead of plain text
+    pub regex_mode: bool,
+    /// Whether the query is currently being edited, in which case all key presses are consumed as text input
+    pub editing: bool,
+    /// The scroll position from when the search was started, used to restore the position when cancelling and as the
+    /// starting point for the incremental search
+    pub origin: Option<u128>,
+    /// Compiled query, if the query is non-empty and valid
+    pub query: Option<Regex>,
+    pub invalid_query: bool,
+    pub no_matches: bool,
+}
+impl OutputSearch {
+    pub fn start(&mut self, origin: Option<u128>) {
+        *self = Self {
+            regex_mode: self.regex_mode,
+            editing: true,
+            origin,
+            ..Self::default()
+        };
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self {
+            regex_mode: self.regex_mode,
+            ..Self::default()
+        };
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.editing || self.query.is_some()
+    }
+
+    /// Recompiles the query after the text or the mode has changed.
+    pub fn update_query(&mut self) {
+        let result = compile_query(&self.text, self.regex_mode);
+        self.invalid_query = result.is_err();
+        self.query = result.unwrap_or(None);
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        self.query
+            .as_ref()
+            .map(|query| query.is_match(value))
+            .unwrap_or(false)
+    }
+
+    /// Splits the given text into parts, so that every match of the query is highlighted with the given colors.
+    pub fn highlight(
+        &self,
+        text: &str,
+        color: Option<Color>,
+        match_fg: Color,
+        match_bg: Color,
+    ) -> Vec<LinePart> {
+        let Some(query) = self.query.as_ref() else {
+            return vec![LinePart {
+                text: text.to_owned(),
+                color,
+                bg: None,
+            }];
+        };
+
+        let mut parts = Vec::new();
+        let mut last_end = 0;
+        for found in query.find_iter(text).filter(|found| !found.is_empty()) {
+            if found.start() > last_end {
+                parts.push(LinePart {
+                    text: text[last_end..found.start()].to_owned(),
+                    color,
+                    bg: None,
+                });
+            }
+            parts.push(LinePart {
+                text: found.as_str().to_owned(),
+                color: Some(match_fg),
+                bg: Some(match_bg),
+            });
+            last_end = found.end();
+        }
+        if last_end < text.len() || parts.is_empty() {
+            parts.push(LinePart {
+                text: text[last_end..].to_owned(),
+                color,
+                bg: None,
+            });
+        }
+
+        parts
+    }
+
+    /// Text displayed in the search prompt at the bottom of the output pane.
+    pub fn prompt(&self) -> String {
+        let mut prompt = format!(
+            "{mode}/{text}",
+            mode = if self.regex_mode { "regex " } else { "" },
+            text = self.text
+        );
+        if self.editing {
+            prompt.push('_');
+        }
+        if self.invalid_query {
+            prompt.push_str("  [invalid regex]");
+        } else if self.no_matches {
+            prompt.push_str("  [no matches]");
+        }
+
+        prompt
+    }
+}
@@ -0,0 +1,68 @@
+use crate::config::ProblemSeverity;
+use crate::system_state::SystemState;
+use ratatui::style::Color;
+use ui::component::{Cell, Component, SimpleList, Text};
+use ui::{FrameContext, RenderArgs, UIResult};
+
+/// Lists the problems extracted from the output of all services.
+pub struct ProblemsPane<'a> {
+    pub system_state: &'a SystemState,
+    pub show_selection: bool,
+}
+impl<'a> ProblemsPane<'a> {
+    pub fn count_problems(system_state: &SystemState) -> usize {
+        system_state
+            .iter_services()
+            .map(|service| service.problems.len())
+            .sum()
+    }
+}
+
+impl<'a> Component for ProblemsPane<'a> {
+    /// Index of the output line of the selected problem, if one was selected
+    type Output = Option<u128>;
+
+    fn render(self, context: &mut FrameContext) -> UIResult<Self::Output> {
+        let theme = &self.system_state.config.settings.theme;
+        let problems: Vec<(String, Color, u128)> = self
+            .system_state
+            .iter_services()
+            .flat_map(|service| {
+                service.problems.iter().map(|problem| {
+                    let (symbol, color) = match problem.severity {
+                        ProblemSeverity::Error => ("E", theme.log_error_color.0),
+                        ProblemSeverity::Warning => ("W", theme.log_warn_color.0),
+                        ProblemSeverity::Info => ("I", theme.log_info_color.0),
+                    };
+                    let label = format!(
+                        "{symbol} {service}/{block} {location}{message}",
+                        service = service.definition.id.inner(),
+                        block = problem.block_id.inner(),
+                        location = problem
+                            .location()
+                            .map(|location| format!("{location} "))
+                            .unwrap_or_default(),
+                        message = problem.message,
+                    );
+                    (label, color, problem.output_index)
+                })
+            })
+            .collect();
+
+        if problems.is_empty() {
+            context.render_component(RenderArgs::new(Text::new("No problems")))?;
+            return Ok(None);
+        }
+
+        let selection = context.render_component(RenderArgs::new(
+            SimpleList::new(
+                "view-profile-problems-list",
+                &problems,
+                |(label, color, _), _| Ok(Cell::new(Text::new(label.clone()).fg(Some(*color)))),
+            )
+            .highlight_visible(self.show_selection),
+        ))?;
+
+        Ok(selection.map(|selection| problems[selection.selected_index].2))
+    }
+}
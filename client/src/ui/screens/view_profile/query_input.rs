@@ -0,0 +1,73 @@
+use crate::ui::inputs::ATTR_KEY_TOGGLE_QUERY_REGEX;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use regex::Regex;
+use ui::input::KeyMatcherQueryable;
+use ui::{FrameContext, UIResult};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum QueryInput {
+    Unchanged,
+    Changed,
+    Confirmed,
+    Cancelled,
+}
+
+/// Processes the key presses of a frame as text input for a query. `regex_mode` is toggled with its own keybind.
+pub fn process_query_input(
+    context: &FrameContext,
+    text: &mut String,
+    regex_mode: &mut bool,
+) -> UIResult<QueryInput> {
+    let mut result = QueryInput::Unchanged;
+
+    if context
+        .signals()
+        .is_key_pressed(context.req_attr(ATTR_KEY_TOGGLE_QUERY_REGEX)?)
+    {
+        *regex_mode = !*regex_mode;
+        result = QueryInput::Changed;
+    }
+
+    for event in context.signals().matching::<KeyEvent>() {
+        if event.kind != KeyEventKind::Press {
+            continue;
+        }
+        match event.code {
+            KeyCode::Esc => return Ok(QueryInput::Cancelled),
+            KeyCode::Enter => return Ok(QueryInput::Confirmed),
+            KeyCode::Backspace => {
+                text.pop();
+                result = QueryInput::Changed;
+            }
+            KeyCode::Char(char)
+                if !event
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                text.push(char);
+                result = QueryInput::Changed;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(result)
+}
+
+/// Compiles a query into a regex. Plain text queries are case-insensitive unless they contain uppercase characters.
+/// Empty queries result in `None`.
+pub fn compile_query(text: &str, regex_mode: bool) -> Result<Option<Regex>, regex::Error> {
+    if text.is_empty() {
+        Ok(None)
+    } else if regex_mode {
+        Regex::new(text).map(Some)
+    } else {
+        let case_insensitive = !text.chars().any(|char| char.is_uppercase());
+        Regex::new(&format!(
+            "{flags}{text}",
+            flags = if case_insensitive { "(?i)" } else { "" },
+            text = regex::escape(text)
+        ))
+        .map(Some)
+    }
+}
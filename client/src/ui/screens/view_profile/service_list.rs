@@ -216,7 +216,8 @@ pub struct ServiceListState {
 
 impl<'a> StatefulComponent for ServiceList<'a> {
     type State = ServiceListState;
-    type Output = ();
+    /// Index of the selected service
+    type Output = usize;
 
     fn state_id(&self) -> &str {
         "view-profile-service-list"
@@ -275,6 +276,12 @@ impl<'a> StatefulComponent for ServiceList<'a> {
                                     }
                                     _ => BlockUIStatus::Working,
                                 },
+                                BlockStatus::Ok { liveness, .. } if liveness.unhealthy => {
+                                    BlockUIStatus::Unhealthy
+                                }
+                                BlockStatus::Ok { liveness, .. } if liveness.is_degraded() => {
+                                    BlockUIStatus::Degraded
+                                }
                                 BlockStatus::Ok { .. } => BlockUIStatus::Ok,
                                 BlockStatus::Error => BlockUIStatus::Failed,
                                 BlockStatus::Disabled => BlockUIStatus::Disabled,
@@ -308,7 +315,8 @@ impl<'a> StatefulComponent for ServiceList<'a> {
                                     BlockUIStatus::WaitingToProcess => waiting_color,
                                     BlockUIStatus::Working => processing_color,
                                     BlockUIStatus::Ok => active_color,
-                                    BlockUIStatus::Failed => error_color,
+                                    BlockUIStatus::Degraded => waiting_color,
+                                    BlockUIStatus::Unhealthy | BlockUIStatus::Failed => error_color,
                                 },
                             ),
                             FlowableArgs { fill: false },
@@ -370,6 +378,16 @@ impl<'a> StatefulComponent for ServiceList<'a> {
                     );
                 }
 
+                flow = flow.element(
+                    Text::new(if service.unseen_errors > 0 {
+                        format!("{:>2}", service.unseen_errors.min(99))
+                    } else {
+                        "  ".to_owned()
+                    })
+                    .fg(error_color),
+                    FlowableArgs { fill: false },
+                );
+
                 flow = flow.element(Spinner::new(is_processing), FlowableArgs { fill: false });
 
                 Ok(flow)
@@ -381,7 +399,7 @@ impl<'a> StatefulComponent for ServiceList<'a> {
             }),
         ))?;
 
-        Ok(())
+        Ok(state.selection)
     }
 }
 
@@ -404,7 +422,7 @@ impl<'a> MeasurableComponent for ServiceList<'a> {
         let slot_display_width = self.resolve_slots()?.iter().map(|s| s.size).sum::<usize>();
 
         Ok(Size {
-            width: (longest_name + slot_display_width + 6) as u16,
+            width: (longest_name + slot_display_width + 8) as u16,
             height: services.len() as u16,
         })
     }
@@ -422,4 +440,8 @@ pub enum BlockUIStatus {
     WaitingToProcess,
     Failed,
     Ok,
+    /// Healthy, but the latest liveness checks failed
+    Degraded,
+    /// Liveness checks failed often enough in a row for the block to be considered unhealthy
+    Unhealthy,
 }
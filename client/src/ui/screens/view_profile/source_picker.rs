@@ -0,0 +1,98 @@
+use crate::models::{OutputKey, OutputKind};
+use crate::system_state::SystemState;
+use crate::ui::theming::ATTR_COLOR_FOCUSED_ELEMENT;
+use ratatui::style::Color;
+use ui::component::{Align, Cell, Component, SimpleList, Text};
+use ui::{FrameContext, RenderArgs, UIResult};
+
+/// Popup listing every output source, allowing each of them to be shown or hidden individually.
+pub struct SourcePicker<'a> {
+    pub system_state: &'a mut SystemState,
+}
+impl<'a> SourcePicker<'a> {
+    fn label(&self, key: &OutputKey) -> String {
+        let name = key
+            .service_id
+            .as_ref()
+            .map(|id| id.inner())
+            .or(self.system_state.get_profile_name())
+            .unwrap_or_default();
+        let kind = match key.kind {
+            OutputKind::System => "i",
+            OutputKind::ExtProcess => "c",
+        };
+
+        format!("{kind}/{name}/{source}", source = key.source_name)
+    }
+}
+
+impl<'a> Component for SourcePicker<'a> {
+    type Output = ();
+
+    fn render(self, context: &mut FrameContext) -> UIResult<Self::Output> {
+        let max_width = context.size().width / 2;
+        let max_height = context.size().height / 2;
+        let focused_color = *context.req_attr::<Color>(ATTR_COLOR_FOCUSED_ELEMENT)?;
+
+        let mut keys: Vec<(String, OutputKey)> = self
+            .system_state
+            .output_store
+            .outputs
+            .keys()
+            .map(|key| (self.label(key), key.clone()))
+            .collect();
+        keys.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+        if keys.is_empty() {
+            context.render_component(RenderArgs::new(
+                Cell::new(
+                    Cell::new(Text::new("No output yet"))
+                        .border(focused_color, "Output sources")
+                        .bg(Color::Reset)
+                        .min_width(20),
+                )
+                .align(Align::Center),
+            ))?;
+            return Ok(());
+        }
+
+        let disabled_outputs = &self.system_state.disabled_outputs;
+        let selection = context.render_component(RenderArgs::new(
+            Cell::new(
+                Cell::new(SimpleList::new(
+                    "view-profile-source-picker-list",
+                    &keys,
+                    |(label, key), _| {
+                        let enabled = !disabled_outputs.contains(key);
+                        Ok(Cell::new(
+                            Text::new(format!(
+                                "[{check}] {label}",
+                                check = if enabled { "x" } else { " " }
+                            ))
+                            .fg(if enabled {
+                                None
+                            } else {
+                                Some(Color::Gray)
+                            }),
+                        ))
+                    },
+                ))
+                .border(focused_color, "Output sources")
+                .bg(Color::Reset)
+                .min_width(20)
+                .max_width(max_width)
+                .max_height(max_height),
+            )
+            .align(Align::Center),
+        ))?;
+
+        if let Some(selection) = selection {
+            let (_, key) = &keys[selection.selected_index];
+            if !self.system_state.disabled_outputs.remove(key) {
+                self.system_state.disabled_outputs.insert(key.clone());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::{DateTime, Local, NaiveTime, TimeDelta};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum TimestampMode {
+    #[default]
+    Hidden,
+    /// Wall-clock time at which the line was received
+    Absolute,
+    /// Time elapsed since the start of the block run that produced the line
+    Relative,
+}
+impl TimestampMode {
+    pub fn next(self) -> Self {
+        match self {
+            TimestampMode::Hidden => TimestampMode::Absolute,
+            TimestampMode::Absolute => TimestampMode::Relative,
+            TimestampMode::Relative => TimestampMode::Hidden,
+        }
+    }
+}
+impl Display for TimestampMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimestampMode::Hidden => write!(f, "Off"),
+            TimestampMode::Absolute => write!(f, "Abs"),
+            TimestampMode::Relative => write!(f, "Rel"),
+        }
+    }
+}
+
+/// Formats the timestamp of a line for display. Relative timestamps fall back to absolute ones if the start of the
+/// run is not known, such as for lines produced by tasks.
+pub fn format_timestamp(
+    mode: TimestampMode,
+    timestamp: &DateTime<Local>,
+    run_start: Option<DateTime<Local>>,
+) -> Option<String> {
+    match (mode, run_start) {
+        (TimestampMode::Hidden, _) => None,
+        (TimestampMode::Relative, Some(run_start)) => {
+            let elapsed = timestamp.signed_duration_since(run_start);
+            let millis = elapsed.num_milliseconds().max(0);
+            let (hours, minutes, seconds, millis) = (
+                millis / 3_600_000,
+                millis / 60_000 % 60,
+                millis / 1000 % 60,
+                millis % 1000,
+            );
+            Some(if hours > 0 {
+                format!("+{hours}:{minutes:02}:{seconds:02}.{millis:03}")
+            } else {
+                format!("+{minutes:02}:{seconds:02}.{millis:03}")
+            })
+        }
+        (TimestampMode::Absolute | TimestampMode::Relative, _) => {
+            Some(timestamp.format("%H:%M:%S%.3f").to_string())
+        }
+    }
+}
+
+/// Parses a point in time to jump to. Accepts a time of the current day (`HH:MM`, `HH:MM:SS` or `HH:MM:SS.fff`), or a
+/// duration relative to now prefixed with a minus sign, such as `-5m` or `-1h 30m`.
+pub fn parse_jump_target(text: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let text = text.trim();
+    if let Some(duration) = text.strip_prefix('-') {
+        let duration = humantime::parse_duration(duration.trim()).ok()?;
+        return Some(now - TimeDelta::from_std(duration).ok()?);
+    }
+
+    let time = ["%H:%M:%S%.f", "%H:%M:%S", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(text, format).ok())?;
+    now.date_naive()
+        .and_time(time)
+        .and_local_timezone(Local)
+        .earliest()
+}
@@ -10,6 +10,7 @@ def simulate_gradle_build():
     start_time = time.time()
 
     print_with_delay("> Task :compileJava")
+    print_with_delay("src/main/java/com/example/App.java:12: warning: [deprecation] Date(int,int,int) in Date has been deprecated")
     print_with_delay("> Task :processResources")
     print_with_delay("> Task :classes")
     print_with_delay("> Task :compileTestJava")
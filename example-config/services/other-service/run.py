@@ -1,5 +1,6 @@
 #!/usr/bin/env python3
 import http.server
+import json
 import random
 import socketserver
 import time
@@ -10,12 +11,26 @@ from http import HTTPStatus
 def human_time(seconds: float) -> str:
     return f"{seconds:.2f}s"
 
+def log(level: str, msg: str, **fields):
+    print(json.dumps({"level": level, "time": datetime.now().isoformat(), "msg": msg, **fields}), flush=True)
+
 class Handler(http.server.SimpleHTTPRequestHandler):
     def do_GET(self):
+        if self.path == "/status":
+            body = json.dumps({"status": "UP", "components": {"db": {"status": "UP"}}}).encode()
+            self.send_response(HTTPStatus.OK)
+            self.send_header("Content-Type", "application/json")
+            self.end_headers()
+            self.wfile.write(body)
+            return
+
         self.send_response(HTTPStatus.OK)
         self.end_headers()
         self.wfile.write(b'Response body')
 
+    def log_message(self, format, *args):
+        log("info", "Handled request", path=self.path, client=self.client_address[0])
+
 
 def run_startup_sequence():
     steps = [
@@ -26,11 +41,11 @@ def run_startup_sequence():
     ]
     total = 0.0
     for step in steps:
-        print(f"{datetime.now().isoformat()} - {step}", flush=True)
+        log("info", step)
         delay = random.uniform(0, 0.5)
         time.sleep(delay)
         total += delay
-    print(f"{datetime.now().isoformat()} - Startup complete (took {total:.2f}s)", flush=True)
+    log("info", "Startup complete", duration=human_time(total))
 
 def serve(port: int):
     socketserver.TCPServer.allow_reuse_address=True